@@ -0,0 +1,216 @@
+use crate::polygon::*;
+use crate::triangle::*;
+use crate::union_find::*;
+use crate::vertex::*;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::mem;
+use std::rc::Rc;
+
+/* Alpha shape as the union of the Delaunay triangles whose circumradius
+does not exceed alpha.
+
+    - Triangles sharing an edge belong to the same polygon.
+    - Boundary edges are followed with the shape on their left, turning
+    around each vertex through the triangles of the shape, so polygons
+    touching at a vertex stay apart.
+
+Each polygon has one counterclockwise exterior ring and a clockwise ring
+for each of its holes. Growing alpha up to the circumradius of the
+largest triangle gives back the triangulation itself.  */
+
+pub struct AlphaPolygon {
+    /* pairs of x,y coordinates, in counterclockwise order */
+    pub exterior: Vec<f64>,
+    /* pairs of x,y coordinates, each in clockwise order */
+    pub holes: Vec<Vec<f64>>,
+}
+
+pub struct AlphaShape {
+    pub alpha: f64,
+    pub triangles: Vec<Rc<Triangle>>,
+    pub polygons: Vec<AlphaPolygon>,
+}
+
+impl AlphaShape {
+    pub(crate) fn from_adjacency(
+        triangles: &HashSet<Rc<Triangle>>,
+        adjacency: &HashMap<(Rc<Vertex>, Rc<Vertex>), Rc<Triangle>>,
+        alpha: f64,
+    ) -> Self {
+        let kept: HashSet<Rc<Triangle>> = triangles
+            .iter()
+            .filter(|triangle| !triangle.is_ghost() && triangle.circumradius() <= alpha)
+            .cloned()
+            .collect();
+        let is_kept = |triangle: Option<&Rc<Triangle>>| match triangle {
+            Some(triangle) => kept.contains(triangle),
+            None => false,
+        };
+
+        /* polygons are the groups of triangles connected by their edges */
+        let mut polygons: Vec<AlphaPolygon> = Vec::new();
+        let mut visited: HashSet<Rc<Triangle>> = HashSet::new();
+        let mut traced: HashSet<(Rc<Vertex>, Rc<Vertex>)> = HashSet::new();
+        for first_triangle in kept.iter() {
+            if visited.contains(first_triangle) {
+                continue;
+            }
+
+            let mut rings: Vec<Vec<f64>> = Vec::new();
+            let mut pending: Vec<Rc<Triangle>> = vec![Rc::clone(first_triangle)];
+            while let Some(triangle) = pending.pop() {
+                if !visited.insert(Rc::clone(&triangle)) {
+                    continue;
+                }
+                for (v1, v2) in triangle.edges() {
+                    let twin = adjacency.get(&(Rc::clone(&v2), Rc::clone(&v1)));
+                    if is_kept(twin) {
+                        pending.push(Rc::clone(twin.unwrap()));
+                    } else if !traced.contains(&(Rc::clone(&v1), Rc::clone(&v2))) {
+                        rings.push(Self::trace_ring(
+                            &triangle,
+                            v1,
+                            v2,
+                            adjacency,
+                            &is_kept,
+                            &mut traced,
+                        ));
+                    }
+                }
+            }
+
+            /* a polygon touching itself at a vertex may close extra counterclockwise rings */
+            let (mut exteriors, holes): (Vec<Vec<f64>>, Vec<Vec<f64>>) =
+                rings.into_iter().partition(|ring| polygon_area(ring) > 0.0);
+            exteriors.sort_by(|r1, r2| polygon_area(r2).partial_cmp(&polygon_area(r1)).unwrap());
+            for (position, exterior) in exteriors.into_iter().enumerate() {
+                let holes = if position == 0 {
+                    holes.clone()
+                } else {
+                    Vec::new()
+                };
+                polygons.push(AlphaPolygon { exterior, holes });
+            }
+        }
+
+        Self {
+            alpha,
+            triangles: kept.into_iter().collect(),
+            polygons,
+        }
+    }
+
+    /**
+     * Smallest alpha whose shape is a single polygon covering every vertex. Triangles
+     * are added by increasing circumradius, merging the groups of triangles they
+     * share an edge with, until one group holds every vertex.
+     */
+    pub(crate) fn optimal_alpha(
+        triangles: &HashSet<Rc<Triangle>>,
+        adjacency: &HashMap<(Rc<Vertex>, Rc<Vertex>), Rc<Triangle>>,
+    ) -> Option<f64> {
+        let mut sorted: Vec<(f64, Rc<Triangle>)> = triangles
+            .iter()
+            .filter(|triangle| !triangle.is_ghost())
+            .map(|triangle| (triangle.circumradius(), Rc::clone(triangle)))
+            .collect();
+        sorted.sort_by(|(r1, _), (r2, _)| r1.partial_cmp(r2).unwrap());
+
+        let vertices_size = sorted
+            .iter()
+            .flat_map(|(_, triangle)| {
+                vec![
+                    Rc::clone(&triangle.v1),
+                    Rc::clone(&triangle.v2),
+                    Rc::clone(&triangle.v3),
+                ]
+            })
+            .collect::<HashSet<Rc<Vertex>>>()
+            .len();
+
+        /* union-find over the added triangles, by position in the sorted list */
+        let positions: HashMap<Rc<Triangle>, usize> = sorted
+            .iter()
+            .enumerate()
+            .map(|(position, (_, triangle))| (Rc::clone(triangle), position))
+            .collect();
+        let mut groups = UnionFind::new(sorted.len());
+        let mut group_vertices: Vec<HashSet<Rc<Vertex>>> = vec![HashSet::new(); sorted.len()];
+        let mut groups_size = 0;
+
+        for (position, (radius, triangle)) in sorted.iter().enumerate() {
+            groups_size += 1;
+            group_vertices[position] = [&triangle.v1, &triangle.v2, &triangle.v3]
+                .iter()
+                .map(|vertex| Rc::clone(vertex))
+                .collect();
+
+            for (v1, v2) in triangle.edges() {
+                let neighbor_position = match adjacency
+                    .get(&(v2, v1))
+                    .and_then(|twin| positions.get(twin))
+                {
+                    Some(&neighbor_position) if neighbor_position < position => neighbor_position,
+                    _ => continue,
+                };
+                if let Some((root, neighbor_root)) = groups.union(position, neighbor_position) {
+                    let merged = mem::take(&mut group_vertices[root]);
+                    group_vertices[neighbor_root].extend(merged);
+                    groups_size -= 1;
+                }
+            }
+
+            let root = groups.find(position);
+            if groups_size == 1 && group_vertices[root].len() == vertices_size {
+                return Some(*radius);
+            }
+        }
+        None
+    }
+
+    /**
+     * Follows the boundary from the edge (v1, v2) of the triangle, with the shape
+     * on the left, until coming back to it.
+     */
+    fn trace_ring<F>(
+        triangle: &Rc<Triangle>,
+        v1: Rc<Vertex>,
+        v2: Rc<Vertex>,
+        adjacency: &HashMap<(Rc<Vertex>, Rc<Vertex>), Rc<Triangle>>,
+        is_kept: &F,
+        traced: &mut HashSet<(Rc<Vertex>, Rc<Vertex>)>,
+    ) -> Vec<f64>
+    where
+        F: Fn(Option<&Rc<Triangle>>) -> bool,
+    {
+        let mut ring: Vec<f64> = Vec::new();
+        let (mut triangle, mut v1, mut v2) = (Rc::clone(triangle), v1, v2);
+        while traced.insert((Rc::clone(&v1), Rc::clone(&v2))) {
+            ring.push(v1.x);
+            ring.push(v1.y);
+
+            /* turns clockwise around v2 through the shape, up to its boundary */
+            loop {
+                let next_vertex = third_vertex(&triangle, &v1, &v2);
+                let twin = adjacency.get(&(Rc::clone(&next_vertex), Rc::clone(&v2)));
+                if is_kept(twin) {
+                    triangle = Rc::clone(twin.unwrap());
+                    v1 = next_vertex;
+                } else {
+                    v1 = mem::replace(&mut v2, next_vertex);
+                    break;
+                }
+            }
+        }
+        ring
+    }
+}
+
+fn third_vertex(triangle: &Triangle, v1: &Rc<Vertex>, v2: &Rc<Vertex>) -> Rc<Vertex> {
+    [&triangle.v1, &triangle.v2, &triangle.v3]
+        .iter()
+        .find(|vertex| **vertex != v1 && **vertex != v2)
+        .map(|vertex| Rc::clone(vertex))
+        .unwrap()
+}
@@ -1,7 +1,6 @@
-extern crate nalgebra;
 use crate::vertex::*;
 
-use nalgebra::Matrix4;
+use num::{BigRational, Signed};
 
 #[derive(PartialEq, Debug)]
 pub enum Continence {
@@ -13,25 +12,62 @@ pub enum Continence {
 /**
  * Checks whether Vertex d is contained by the circumcircle defined by triangle(a,b,c).
  * Vertices a, b and c must be in counterclockwise order.
+ *
+ * The determinant is evaluated relative to d in floating point and, when its
+ * magnitude is within the rounding error bound, again in exact rational
+ * arithmetic. Cocircular vertices are then always found on the boundary, so
+ * insertions never disagree on whether a vertex is in conflict.
  */
 pub fn in_circle(a: &Vertex, b: &Vertex, c: &Vertex, d: &Vertex) -> Continence {
-    let matrix = Matrix4::new(
-        a.x, a.y, a.x.powi(2) + a.y.powi(2), 1.0,
-        b.x, b.y, b.x.powi(2) + b.y.powi(2), 1.0,
-        c.x, c.y, c.x.powi(2) + c.y.powi(2), 1.0,
-        d.x, d.y, d.x.powi(2) + d.y.powi(2), 1.0,
-    );
-    let det = matrix.determinant();
+    let (adx, ady) = (a.x - d.x, a.y - d.y);
+    let (bdx, bdy) = (b.x - d.x, b.y - d.y);
+    let (cdx, cdy) = (c.x - d.x, c.y - d.y);
+    let a_lift = adx * adx + ady * ady;
+    let b_lift = bdx * bdx + bdy * bdy;
+    let c_lift = cdx * cdx + cdy * cdy;
 
-    if det > 0.0 {
-        return Continence::Inside;
-    } else if det < 0.0 {
-        return Continence::Outside;
+    let det = a_lift * (bdx * cdy - cdx * bdy)
+        + b_lift * (cdx * ady - adx * cdy)
+        + c_lift * (adx * bdy - bdx * ady);
+    let permanent = a_lift * ((bdx * cdy).abs() + (cdx * bdy).abs())
+        + b_lift * ((cdx * ady).abs() + (adx * cdy).abs())
+        + c_lift * ((adx * bdy).abs() + (bdx * ady).abs());
+
+    /* Shewchuk's bound for this expression, epsilon being half an ulp of 1 */
+    let epsilon = f64::EPSILON / 2.0;
+    let error_bound = (10.0 + 96.0 * epsilon) * epsilon * permanent;
+
+    if det > error_bound {
+        Continence::Inside
+    } else if det < -error_bound {
+        Continence::Outside
     } else {
-        return Continence::Boundary;
+        exact_in_circle(a, b, c, d)
     }
 }
 
+fn exact_in_circle(a: &Vertex, b: &Vertex, c: &Vertex, d: &Vertex) -> Continence {
+    let exact = |value: f64| BigRational::from_float(value).unwrap();
+    let (dx, dy) = (exact(d.x), exact(d.y));
+    let (adx, ady) = (exact(a.x) - &dx, exact(a.y) - &dy);
+    let (bdx, bdy) = (exact(b.x) - &dx, exact(b.y) - &dy);
+    let (cdx, cdy) = (exact(c.x) - &dx, exact(c.y) - &dy);
+    let a_lift = &adx * &adx + &ady * &ady;
+    let b_lift = &bdx * &bdx + &bdy * &bdy;
+    let c_lift = &cdx * &cdx + &cdy * &cdy;
+
+    let det = a_lift * (&bdx * &cdy - &cdx * &bdy)
+        + b_lift * (&cdx * &ady - &adx * &cdy)
+        + c_lift * (&adx * &bdy - &bdx * &ady);
+
+    if det.is_positive() {
+        Continence::Inside
+    } else if det.is_negative() {
+        Continence::Outside
+    } else {
+        Continence::Boundary
+    }
+}
 
 #[cfg(test)]
 mod in_circle {
@@ -63,4 +99,14 @@ mod in_circle {
         let p4 = Vertex::new(0.0, 1.0);
         assert_eq!(in_circle(&p1, &p2, &p3, &p4), Continence::Boundary);
     }
+
+    #[test]
+    fn test_continence_boundary_off_grid() {
+        /* symmetric around the origin, so exactly cocircular */
+        let p1 = Vertex::new(0.3, 0.1);
+        let p2 = Vertex::new(0.1, 0.3);
+        let p3 = Vertex::new(-0.3, -0.1);
+        let p4 = Vertex::new(-0.1, -0.3);
+        assert_eq!(in_circle(&p1, &p2, &p3, &p4), Continence::Boundary);
+    }
 }
@@ -0,0 +1,170 @@
+use crate::triangulation::*;
+use std::collections::HashMap;
+
+/* Isolines of a scalar field given by a value per vertex, linear over
+each triangle.
+
+    - A vertex is above the level if its value is greater or equal,
+    else below, so the contour never passes through a vertex.
+    - Each triangle with vertices on both sides is crossed by one
+    segment, between points interpolated on two of its edges.
+    - Segments are directed with the values above the level on their
+    left, and chained by their shared edge points.
+
+Chains ending at the boundary of the mesh, the convex hull or a hole,
+are open. The others are closed rings, counterclockwise around higher
+values.  */
+
+pub struct Contour {
+    pub level: f64,
+    /* points as pairs of x,y coordinates, the first not repeated at the end of rings */
+    pub coordinates: Vec<f64>,
+    pub is_closed: bool,
+}
+
+impl Contour {
+    pub(crate) fn trace(triangulation: &Triangulation, values: &[f64], level: f64) -> Vec<Self> {
+        let is_above = |index: usize| values[index] >= level;
+
+        /* crossed edges, as ordered pairs of vertex indices, linked along the contour */
+        let mut next_edges: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+        for triangle in triangulation.triangles.chunks(3) {
+            let mut exit_edge: Option<(usize, usize)> = None;
+            let mut entry_edge: Option<(usize, usize)> = None;
+            for position in 0..3 {
+                let (v1, v2) = (triangle[position], triangle[(position + 1) % 3]);
+                let edge = (v1.min(v2), v1.max(v2));
+                match (is_above(v1), is_above(v2)) {
+                    (true, false) => exit_edge = Some(edge),
+                    (false, true) => entry_edge = Some(edge),
+                    _ => {}
+                }
+            }
+            if let (Some(exit_edge), Some(entry_edge)) = (exit_edge, entry_edge) {
+                next_edges.insert(exit_edge, entry_edge);
+            }
+        }
+
+        let mut contours: Vec<Self> = Vec::new();
+        let mut has_previous: HashMap<(usize, usize), bool> = HashMap::new();
+        for edge in next_edges.values() {
+            has_previous.insert(*edge, true);
+        }
+
+        /* open chains start at an edge no segment leads to */
+        let mut starts: Vec<(usize, usize)> = next_edges
+            .keys()
+            .filter(|edge| !has_previous.contains_key(edge))
+            .cloned()
+            .collect();
+        starts.sort_unstable();
+        for start in starts {
+            let chain = Self::follow(&mut next_edges, start);
+            contours.push(Self::from_edges(
+                triangulation,
+                values,
+                level,
+                &chain,
+                false,
+            ));
+        }
+
+        /* whatever remains is made of rings */
+        while let Some(start) = next_edges.keys().min().cloned() {
+            let mut ring = Self::follow(&mut next_edges, start);
+            ring.pop();
+            contours.push(Self::from_edges(triangulation, values, level, &ring, true));
+        }
+        contours
+    }
+
+    fn follow(
+        next_edges: &mut HashMap<(usize, usize), (usize, usize)>,
+        start: (usize, usize),
+    ) -> Vec<(usize, usize)> {
+        let mut chain = vec![start];
+        let mut edge = start;
+        while let Some(next) = next_edges.remove(&edge) {
+            chain.push(next);
+            edge = next;
+        }
+        chain
+    }
+
+    fn from_edges(
+        triangulation: &Triangulation,
+        values: &[f64],
+        level: f64,
+        edges: &[(usize, usize)],
+        is_closed: bool,
+    ) -> Self {
+        let coordinates = &triangulation.coordinates;
+        let mut points: Vec<f64> = Vec::new();
+        for (v1, v2) in edges.iter() {
+            let ratio = (level - values[*v1]) / (values[*v2] - values[*v1]);
+            points.push(coordinates[v1 * 2] + (coordinates[v2 * 2] - coordinates[v1 * 2]) * ratio);
+            points.push(
+                coordinates[v1 * 2 + 1]
+                    + (coordinates[v2 * 2 + 1] - coordinates[v1 * 2 + 1]) * ratio,
+            );
+        }
+        Self {
+            level,
+            coordinates: points,
+            is_closed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod trace {
+    use super::*;
+    use crate::polygon::*;
+
+    fn square() -> Triangulation {
+        /* four triangles around the center */
+        let coordinates = vec![0.0, 0.0, 2.0, 0.0, 2.0, 2.0, 0.0, 2.0, 1.0, 1.0];
+        let triangles = vec![0, 1, 4, 1, 2, 4, 2, 3, 4, 3, 0, 4];
+        Triangulation::from(coordinates, triangles)
+    }
+
+    #[test]
+    fn test_ring_around_peak() {
+        let values = vec![0.0, 0.0, 0.0, 0.0, 2.0];
+        let contours = Contour::trace(&square(), &values, 1.0);
+        assert_eq!(contours.len(), 1);
+        assert!(contours[0].is_closed);
+        assert_eq!(contours[0].coordinates.len(), 8);
+        /* counterclockwise around the peak */
+        assert_eq!(polygon_area(&contours[0].coordinates), 1.0);
+    }
+
+    #[test]
+    fn test_chain_across_hull() {
+        let values = vec![0.0, 2.0, 2.0, 0.0, 1.0];
+        let contours = Contour::trace(&square(), &values, 0.5);
+        assert_eq!(contours.len(), 1);
+        assert!(!contours[0].is_closed);
+        let coordinates = &contours[0].coordinates;
+        assert_eq!(coordinates.first(), Some(&0.5));
+        assert_eq!(coordinates[coordinates.len() - 2], 0.5);
+        assert!(coordinates.iter().skip(1).step_by(2).any(|&y| y == 0.0));
+        assert!(coordinates.iter().skip(1).step_by(2).any(|&y| y == 2.0));
+    }
+
+    #[test]
+    fn test_level_out_of_range() {
+        let values = vec![0.0, 0.0, 0.0, 0.0, 2.0];
+        assert!(Contour::trace(&square(), &values, 3.0).is_empty());
+    }
+
+    #[test]
+    fn test_levels_in_turn() {
+        let values = vec![0.0, 0.0, 0.0, 0.0, 2.0];
+        let contours = square().contours(&values, &[0.5, 3.0, 1.5]);
+        let levels: Vec<f64> = contours.iter().map(|contour| contour.level).collect();
+        assert_eq!(levels, vec![0.5, 1.5]);
+        /* rings shrink toward the peak */
+        assert!(polygon_area(&contours[0].coordinates) > polygon_area(&contours[1].coordinates));
+    }
+}
@@ -0,0 +1,156 @@
+use crate::continence::*;
+use crate::orientation::*;
+use crate::polygon::*;
+use crate::triangle::*;
+use crate::vertex::*;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+/* Convex hull of the vertices in the mesh, by Andrew's monotone chain.
+
+    - Vertices are sorted by x, then y.
+    - The lower chain runs from left to right and the upper one back,
+    each dropping the last vertex while it makes a clockwise turn.
+
+Vertices lying along a hull edge are kept. The hull does not follow the
+ghost triangles: they surround holes as well, and once a boundary is
+inserted they follow its outer ring, concave or not.  */
+
+pub struct ConvexHull {
+    /* hull vertices in counterclockwise order */
+    pub vertices: Vec<Rc<Vertex>>,
+}
+
+impl ConvexHull {
+    pub(crate) fn from_adjacency(
+        adjacency: &HashMap<(Rc<Vertex>, Rc<Vertex>), Rc<Triangle>>,
+    ) -> Self {
+        let vertices: HashSet<Rc<Vertex>> = adjacency
+            .keys()
+            .map(|(v1, _)| v1)
+            .filter(|vertex| !vertex.is_ghost)
+            .cloned()
+            .collect();
+        Self::from_vertices(vertices.into_iter().collect())
+    }
+
+    pub(crate) fn from_vertices(mut vertices: Vec<Rc<Vertex>>) -> Self {
+        vertices.sort_by(|v1, v2| (v1.x, v1.y).partial_cmp(&(v2.x, v2.y)).unwrap());
+        vertices.dedup();
+        if vertices.len() < 3 {
+            return Self { vertices };
+        }
+
+        let mut hull: Vec<Rc<Vertex>> = Vec::new();
+        for chain in [vertices.clone(), vertices.into_iter().rev().collect()].iter() {
+            let chain_start = hull.len();
+            for vertex in chain.iter() {
+                while hull.len() >= chain_start + 2
+                    && orient_2d(&hull[hull.len() - 2], &hull[hull.len() - 1], vertex)
+                        == Orientation::Clockwise
+                {
+                    hull.pop();
+                }
+                hull.push(Rc::clone(vertex));
+            }
+            /* the last vertex starts the other chain */
+            hull.pop();
+        }
+        Self { vertices: hull }
+    }
+
+    /**
+     * Hull vertices as pairs of x,y coordinates.
+     */
+    pub fn coordinates(&self) -> Vec<f64> {
+        self.vertices
+            .iter()
+            .flat_map(|vertex| vec![vertex.x, vertex.y])
+            .collect()
+    }
+
+    pub fn area(&self) -> f64 {
+        polygon_area(&self.coordinates())
+    }
+
+    pub fn perimeter(&self) -> f64 {
+        let size = self.vertices.len();
+        (0..size)
+            .map(|index| {
+                let (v1, v2) = (&self.vertices[index], &self.vertices[(index + 1) % size]);
+                (v2.x - v1.x).hypot(v2.y - v1.y)
+            })
+            .sum()
+    }
+
+    /**
+     * Checks whether the vertex lies inside the hull, on its boundary or outside.
+     */
+    pub fn contains(&self, vertex: &Vertex) -> Continence {
+        let size = self.vertices.len();
+        if size < 3 {
+            return Continence::Outside;
+        }
+
+        let mut continence = Continence::Inside;
+        for index in 0..size {
+            let (v1, v2) = (&self.vertices[index], &self.vertices[(index + 1) % size]);
+            match orient_2d(v1, v2, vertex) {
+                Orientation::Clockwise => return Continence::Outside,
+                Orientation::Colinear => continence = Continence::Boundary,
+                Orientation::Counterclockwise => {}
+            }
+        }
+        continence
+    }
+}
+
+#[cfg(test)]
+mod measures {
+    use super::*;
+
+    fn square() -> ConvexHull {
+        ConvexHull {
+            vertices: vec![
+                Rc::new(Vertex::new(0.0, 0.0)),
+                Rc::new(Vertex::new(2.0, 0.0)),
+                Rc::new(Vertex::new(2.0, 2.0)),
+                Rc::new(Vertex::new(0.0, 2.0)),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_area_and_perimeter() {
+        let hull = square();
+        assert_eq!(hull.area(), 4.0);
+        assert_eq!(hull.perimeter(), 8.0);
+    }
+
+    #[test]
+    fn test_contains() {
+        let hull = square();
+        assert_eq!(hull.contains(&Vertex::new(1.0, 1.0)), Continence::Inside);
+        assert_eq!(hull.contains(&Vertex::new(2.0, 1.0)), Continence::Boundary);
+        assert_eq!(hull.contains(&Vertex::new(0.0, 0.0)), Continence::Boundary);
+        assert_eq!(hull.contains(&Vertex::new(3.0, 1.0)), Continence::Outside);
+    }
+}
+
+#[cfg(test)]
+mod from_vertices {
+    use super::*;
+
+    #[test]
+    fn test_keeps_vertices_along_edges() {
+        let vertices = Vertex::from_coordinates(vec![
+            1.0, 1.0, 0.0, 0.0, 2.0, 2.0, 1.0, 0.0, 2.0, 0.0, 0.0, 2.0, 0.5, 1.5,
+        ]);
+        let hull = ConvexHull::from_vertices(vertices);
+        assert_eq!(
+            hull.coordinates(),
+            vec![0.0, 0.0, 1.0, 0.0, 2.0, 0.0, 2.0, 2.0, 0.0, 2.0]
+        );
+    }
+}
@@ -0,0 +1,720 @@
+use crate::parse_error::*;
+use crate::triangulation::*;
+use crate::triangulator::*;
+use crate::vertex::*;
+#[cfg(feature = "geojson")]
+use serde::Deserialize;
+#[cfg(feature = "geojson")]
+use serde_json::value::RawValue;
+use std::collections::HashMap;
+#[cfg(feature = "geojson")]
+use std::convert::TryFrom;
+use std::fmt::Write;
+use std::rc::Rc;
+
+/* GeoJSON and WKT geometries.
+
+    - Input: Polygon, MultiPolygon and MultiPoint, also found within
+    features, feature collections and geometry collections. Interior
+    rings of polygons are holes.
+    - Output: each triangle as a polygon, in a GeoJSON FeatureCollection
+    with the regional attribute and other values as properties, or in a
+    WKT TIN or MULTIPOLYGON.
+
+Positions beyond x and y, as elevations, are ignored on input. Output
+positions carry the elevations of the triangulation, if any. Reading
+GeoJSON needs the `geojson` feature, which parses with serde_json.  */
+
+pub struct Geometry {
+    /* rings of each polygon as pairs of x,y coordinates, exterior first, not closed */
+    pub polygons: Vec<Vec<Vec<f64>>>,
+    /* pairs of x,y coordinates of loose points */
+    pub points: Vec<f64>,
+}
+
+impl Geometry {
+    /**
+     * Triangulates the vertices of the rings and the points. With polygons, the
+     * rings become constrained segments and whatever lies outside the polygons,
+     * loose points included, is removed. Vertices shared by rings are merged.
+     * Fails on a ring of fewer than 3 distinct positions, or when the vertices
+     * do not span a triangle.
+     */
+    pub fn triangulate(&self) -> Result<Triangulator, String> {
+        let mut vertices: Vec<Rc<Vertex>> = Vec::new();
+        let mut known_vertices: HashMap<Rc<Vertex>, Rc<Vertex>> = HashMap::new();
+        let mut vertex_at = |x: f64, y: f64| -> Rc<Vertex> {
+            let vertex = Rc::new(Vertex::new(x, y));
+            match known_vertices.get(&vertex) {
+                Some(known) => Rc::clone(known),
+                None => {
+                    known_vertices.insert(Rc::clone(&vertex), Rc::clone(&vertex));
+                    vertices.push(Rc::clone(&vertex));
+                    vertex
+                }
+            }
+        };
+
+        let mut rings: Vec<Vec<Rc<Vertex>>> = Vec::new();
+        for ring in self.polygons.iter().flatten() {
+            let mut ring: Vec<Rc<Vertex>> = ring
+                .chunks(2)
+                .map(|point| vertex_at(point[0], point[1]))
+                .collect();
+            /* repeated positions would make segments of no length */
+            ring.dedup();
+            while ring.len() > 1 && ring.first() == ring.last() {
+                ring.pop();
+            }
+            if ring.len() < 3 {
+                return Err("expected a ring of 3 distinct positions or more".to_string());
+            }
+            rings.push(ring);
+        }
+        for point in self.points.chunks(2) {
+            vertex_at(point[0], point[1]);
+        }
+
+        let mut triangulator = Triangulator::from_vertices(vertices);
+        if !triangulator.spans_triangle() {
+            return Err("expected 3 positions or more, not all on a line".to_string());
+        }
+        triangulator.triangulate();
+        if !rings.is_empty() {
+            triangulator.insert_boundary(&rings);
+        }
+        Ok(triangulator)
+    }
+}
+
+#[cfg(feature = "geojson")]
+pub fn read_geojson(text: &str) -> Result<Geometry, ParseError> {
+    let mut geometry = empty_geometry();
+    add_geojson(text, text, &mut geometry)?;
+    Ok(geometry)
+}
+
+/**
+ * Reads a Polygon, MultiPolygon, TIN, MultiPoint or GeometryCollection. Z and M
+ * values are allowed and ignored.
+ */
+pub fn read_wkt(text: &str) -> Result<Geometry, ParseError> {
+    let mut reader = WktReader {
+        tokens: wkt_tokens(text),
+        position: 0,
+    };
+    let mut geometry = empty_geometry();
+    reader.geometry(&mut geometry)?;
+    if let Some((line, token)) = reader.tokens.get(reader.position) {
+        return Err(ParseError::new(
+            *line,
+            &format!("unexpected '{}' after geometry", token),
+        ));
+    }
+    Ok(geometry)
+}
+
+/**
+ * Writes a FeatureCollection with a Polygon feature per triangle. Properties
+ * hold the regional attribute, then the given values, one per triangle.
+ */
+pub fn write_geojson(triangulation: &Triangulation, properties: &[(&str, &[f64])]) -> String {
+    let mut text = String::new();
+    write!(text, "{{\"type\":\"FeatureCollection\",\"features\":[").unwrap();
+    for (index, triangle) in triangulation.triangles.chunks(3).enumerate() {
+        if index > 0 {
+            write!(text, ",").unwrap();
+        }
+        write!(
+            text,
+            "\n{{\"type\":\"Feature\",\"geometry\":{{\"type\":\"Polygon\",\"coordinates\":[["
+        )
+        .unwrap();
+        for (position, vertex) in triangle.iter().chain(triangle.first()).enumerate() {
+            if position > 0 {
+                write!(text, ",").unwrap();
+            }
+            write!(text, "[{}]", position_of(triangulation, *vertex, ",")).unwrap();
+        }
+        write!(
+            text,
+            "]]}},\"properties\":{{\"attribute\":{}",
+            triangulation.attributes.get(index).unwrap_or(&0.0)
+        )
+        .unwrap();
+        for (name, values) in properties.iter() {
+            write!(text, ",\"{}\":{}", escape_json(name), values[index]).unwrap();
+        }
+        write!(text, "}}}}").unwrap();
+    }
+    writeln!(text, "\n]}}").unwrap();
+    text
+}
+
+pub fn write_wkt_tin(triangulation: &Triangulation) -> String {
+    write_wkt(triangulation, "TIN")
+}
+
+pub fn write_wkt_multipolygon(triangulation: &Triangulation) -> String {
+    write_wkt(triangulation, "MULTIPOLYGON")
+}
+
+/* triangles as closed rings, each wrapped as a polygon */
+fn write_wkt(triangulation: &Triangulation, tag: &str) -> String {
+    let mut text = String::from(tag);
+    if !triangulation.z.is_empty() {
+        text.push_str(" Z");
+    }
+    if triangulation.triangles.is_empty() {
+        text.push_str(" EMPTY");
+        return text;
+    }
+    text.push_str(" (");
+    for (index, triangle) in triangulation.triangles.chunks(3).enumerate() {
+        if index > 0 {
+            text.push_str(", ");
+        }
+        text.push_str("((");
+        let positions: Vec<String> = triangle
+            .iter()
+            .chain(triangle.first())
+            .map(|vertex| position_of(triangulation, *vertex, " "))
+            .collect();
+        text.push_str(&positions.join(", "));
+        text.push_str("))");
+    }
+    text.push(')');
+    text
+}
+
+fn position_of(triangulation: &Triangulation, vertex: usize, separator: &str) -> String {
+    let mut position = format!(
+        "{}{}{}",
+        triangulation.coordinates[vertex * 2],
+        separator,
+        triangulation.coordinates[vertex * 2 + 1]
+    );
+    if let Some(z) = triangulation.z.get(vertex) {
+        write!(position, "{}{}", separator, z).unwrap();
+    }
+    position
+}
+
+fn escape_json(text: &str) -> String {
+    let mut escaped = String::new();
+    for character in text.chars() {
+        match character {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            character if (character as u32) < 0x20 => {
+                write!(escaped, "\\u{:04x}", character as u32).unwrap()
+            }
+            character => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+fn empty_geometry() -> Geometry {
+    Geometry {
+        polygons: Vec::new(),
+        points: Vec::new(),
+    }
+}
+
+/* Members of a GeoJSON object, left as JSON text until its type is known */
+#[cfg(feature = "geojson")]
+#[derive(Deserialize)]
+struct GeoJsonObject<'a> {
+    #[serde(rename = "type")]
+    object_type: String,
+    #[serde(borrow)]
+    features: Option<Vec<&'a RawValue>>,
+    #[serde(borrow)]
+    geometry: Option<&'a RawValue>,
+    #[serde(borrow)]
+    geometries: Option<Vec<&'a RawValue>>,
+    #[serde(borrow)]
+    coordinates: Option<&'a RawValue>,
+}
+
+#[cfg(feature = "geojson")]
+#[derive(Deserialize)]
+#[serde(try_from = "Vec<f64>")]
+struct Position(f64, f64);
+
+#[cfg(feature = "geojson")]
+impl TryFrom<Vec<f64>> for Position {
+    type Error = String;
+
+    fn try_from(numbers: Vec<f64>) -> Result<Self, Self::Error> {
+        match numbers[..] {
+            [x, y, ..] => Ok(Position(x, y)),
+            _ => Err("expected a position".to_string()),
+        }
+    }
+}
+
+/* pairs of x,y coordinates, not closed */
+#[cfg(feature = "geojson")]
+#[derive(Deserialize)]
+#[serde(try_from = "Vec<Position>")]
+struct Ring(Vec<f64>);
+
+#[cfg(feature = "geojson")]
+impl TryFrom<Vec<Position>> for Ring {
+    type Error = String;
+
+    fn try_from(positions: Vec<Position>) -> Result<Self, Self::Error> {
+        let ring: Vec<f64> = positions
+            .iter()
+            .flat_map(|Position(x, y)| vec![*x, *y])
+            .collect();
+        close_ring(ring, 0).map(Ring).map_err(|error| error.message)
+    }
+}
+
+/**
+ * Drops the repeated first position closing a ring, checking that a polygon
+ * remains.
+ */
+fn close_ring(mut ring: Vec<f64>, line: usize) -> Result<Vec<f64>, ParseError> {
+    let size = ring.len();
+    if size >= 4 && ring[..2] == ring[size - 2..] {
+        ring.truncate(size - 2);
+    }
+    if ring.len() < 6 {
+        return Err(ParseError::new(
+            line,
+            "expected a ring of 3 positions or more",
+        ));
+    }
+    Ok(ring)
+}
+
+/* line of a slice of the text, counting from 1 */
+#[cfg(feature = "geojson")]
+fn line_of(text: &str, json: &str) -> usize {
+    let offset = json.as_ptr() as usize - text.as_ptr() as usize;
+    text[..offset].matches('\n').count() + 1
+}
+
+/**
+ * Parses a slice of the text, reporting errors at their line in the text.
+ */
+#[cfg(feature = "geojson")]
+fn from_json<'a, T: Deserialize<'a>>(text: &str, json: &'a str) -> Result<T, ParseError> {
+    serde_json::from_str(json).map_err(|error| {
+        /* the message without the position serde_json appends */
+        let message = error.to_string();
+        let message = match message.rfind(" at line ") {
+            Some(end) => &message[..end],
+            None => &message,
+        };
+        let line = if error.is_eof() {
+            0
+        } else {
+            line_of(text, json) + error.line().max(1) - 1
+        };
+        ParseError::new(line, message)
+    })
+}
+
+#[cfg(feature = "geojson")]
+fn member<T>(value: Option<T>, line: usize, name: &str) -> Result<T, ParseError> {
+    value.ok_or_else(|| ParseError::new(line, &format!("expected member '{}'", name)))
+}
+
+#[cfg(feature = "geojson")]
+fn add_geojson(text: &str, json: &str, geometry: &mut Geometry) -> Result<(), ParseError> {
+    let object: GeoJsonObject = from_json(text, json)?;
+    let line = line_of(text, json);
+    match object.object_type.as_str() {
+        "FeatureCollection" => {
+            for feature in member(object.features, line, "features")? {
+                add_geojson(text, feature.get(), geometry)?;
+            }
+        }
+        "Feature" => {
+            if let Some(feature_geometry) = object.geometry {
+                add_geojson(text, feature_geometry.get(), geometry)?;
+            }
+        }
+        "GeometryCollection" => {
+            for item in member(object.geometries, line, "geometries")? {
+                add_geojson(text, item.get(), geometry)?;
+            }
+        }
+        "Polygon" => {
+            let coordinates = member(object.coordinates, line, "coordinates")?;
+            add_polygon(from_json(text, coordinates.get())?, geometry);
+        }
+        "MultiPolygon" => {
+            let coordinates = member(object.coordinates, line, "coordinates")?;
+            let polygons: Vec<Vec<Ring>> = from_json(text, coordinates.get())?;
+            for polygon in polygons {
+                add_polygon(polygon, geometry);
+            }
+        }
+        "MultiPoint" => {
+            let coordinates = member(object.coordinates, line, "coordinates")?;
+            let positions: Vec<Position> = from_json(text, coordinates.get())?;
+            for Position(x, y) in positions {
+                geometry.points.push(x);
+                geometry.points.push(y);
+            }
+        }
+        other => {
+            return Err(ParseError::new(
+                line,
+                &format!("unsupported type '{}'", other),
+            ))
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "geojson")]
+fn add_polygon(rings: Vec<Ring>, geometry: &mut Geometry) {
+    if !rings.is_empty() {
+        geometry
+            .polygons
+            .push(rings.into_iter().map(|Ring(ring)| ring).collect());
+    }
+}
+
+/* Words, numbers and punctuation of WKT text, with their line */
+fn wkt_tokens(text: &str) -> Vec<(usize, String)> {
+    let mut tokens: Vec<(usize, String)> = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let spaced = line
+            .replace('(', " ( ")
+            .replace(')', " ) ")
+            .replace(',', " , ");
+        for token in spaced.split_whitespace() {
+            tokens.push((index + 1, token.to_string()));
+        }
+    }
+    tokens
+}
+
+struct WktReader {
+    tokens: Vec<(usize, String)>,
+    position: usize,
+}
+
+impl WktReader {
+    fn next(&mut self, expected: &str) -> Result<(usize, String), ParseError> {
+        match self.tokens.get(self.position) {
+            Some(token) => {
+                self.position += 1;
+                Ok(token.clone())
+            }
+            None => Err(ParseError::new(0, &format!("expected {}", expected))),
+        }
+    }
+
+    fn peek(&self) -> Option<&str> {
+        self.tokens
+            .get(self.position)
+            .map(|(_, token)| token.as_str())
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), ParseError> {
+        let (line, token) = self.next(&format!("'{}'", expected))?;
+        if token != expected {
+            return Err(ParseError::new(
+                line,
+                &format!("expected '{}', found '{}'", expected, token),
+            ));
+        }
+        Ok(())
+    }
+
+    /**
+     * Reads items separated by commas within parentheses.
+     */
+    fn list<F>(&mut self, mut item: F) -> Result<(), ParseError>
+    where
+        F: FnMut(&mut Self) -> Result<(), ParseError>,
+    {
+        self.expect("(")?;
+        loop {
+            item(self)?;
+            let (line, token) = self.next("')'")?;
+            match token.as_str() {
+                "," => continue,
+                ")" => return Ok(()),
+                _ => {
+                    return Err(ParseError::new(
+                        line,
+                        &format!("expected ',' or ')', found '{}'", token),
+                    ))
+                }
+            }
+        }
+    }
+
+    fn position(&mut self, coordinates: &mut Vec<f64>) -> Result<(), ParseError> {
+        let mut numbers: Vec<f64> = Vec::new();
+        while let Some(token) = self.peek() {
+            if token == "," || token == ")" {
+                break;
+            }
+            let (line, token) = self.next("a number")?;
+            match token.parse::<f64>() {
+                Ok(number) => numbers.push(number),
+                Err(_) => {
+                    return Err(ParseError::new(
+                        line,
+                        &format!("invalid number '{}'", token),
+                    ))
+                }
+            }
+        }
+        if numbers.len() < 2 {
+            let line = self.tokens[self.position.min(self.tokens.len()) - 1].0;
+            return Err(ParseError::new(line, "expected a position"));
+        }
+        coordinates.push(numbers[0]);
+        coordinates.push(numbers[1]);
+        Ok(())
+    }
+
+    fn polygon(&mut self, geometry: &mut Geometry) -> Result<(), ParseError> {
+        let mut rings: Vec<Vec<f64>> = Vec::new();
+        self.list(|reader| {
+            let line = reader.tokens[reader.position.min(reader.tokens.len() - 1)].0;
+            let mut ring: Vec<f64> = Vec::new();
+            reader.list(|reader| reader.position(&mut ring))?;
+            rings.push(close_ring(ring, line)?);
+            Ok(())
+        })?;
+        geometry.polygons.push(rings);
+        Ok(())
+    }
+
+    /**
+     * Reads a tagged geometry, skipping the Z, M or ZM dimensions and
+     * returning early when EMPTY.
+     */
+    fn geometry(&mut self, geometry: &mut Geometry) -> Result<(), ParseError> {
+        let (line, tag) = self.next("a geometry")?;
+        if let Some(dimensions) = self.peek() {
+            if ["Z", "M", "ZM"].contains(&dimensions.to_uppercase().as_str()) {
+                self.position += 1;
+            }
+        }
+        if self.peek().map(|token| token.to_uppercase()) == Some("EMPTY".to_string()) {
+            self.position += 1;
+            return Ok(());
+        }
+
+        match tag.to_uppercase().as_str() {
+            "POLYGON" => self.polygon(geometry),
+            "MULTIPOLYGON" | "TIN" => self.list(|reader| reader.polygon(geometry)),
+            "MULTIPOINT" => self.list(|reader| {
+                /* points may be wrapped in parentheses or not */
+                if reader.peek() == Some("(") {
+                    reader.list(|reader| reader.position(&mut geometry.points))
+                } else {
+                    reader.position(&mut geometry.points)
+                }
+            }),
+            "GEOMETRYCOLLECTION" => self.list(|reader| reader.geometry(geometry)),
+            _ => Err(ParseError::new(
+                line,
+                &format!("unsupported geometry '{}'", tag),
+            )),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "geojson"))]
+mod read_geojson {
+    use super::*;
+    use crate::polygon::*;
+
+    #[test]
+    fn test_polygon_with_hole_and_points() {
+        let text = r#"{
+            "type": "FeatureCollection",
+            "features": [
+                {
+                    "type": "Feature",
+                    "properties": {"name": "field \"A\"", "area": 15, "valid": true},
+                    "geometry": {
+                        "type": "Polygon",
+                        "coordinates": [
+                            [[0, 0], [4, 0], [4, 4], [0, 4], [0, 0]],
+                            [[1, 1], [1, 3], [3, 3], [3, 1], [1, 1]]
+                        ]
+                    }
+                },
+                {
+                    "type": "Feature",
+                    "properties": null,
+                    "geometry": {"type": "MultiPoint", "coordinates": [[5.5, 1e0, 12.0]]}
+                }
+            ]
+        }"#;
+        let geometry = read_geojson(text).unwrap();
+        assert_eq!(geometry.polygons.len(), 1);
+        assert_eq!(
+            geometry.polygons[0][0],
+            vec![0.0, 0.0, 4.0, 0.0, 4.0, 4.0, 0.0, 4.0]
+        );
+        assert_eq!(geometry.polygons[0][1].len(), 8);
+        assert_eq!(geometry.points, vec![5.5, 1.0]);
+
+        /* the point lies outside the polygon, the edge facing it may be split */
+        let triangulation = geometry.triangulate().unwrap().export();
+        assert!(triangulation
+            .coordinates
+            .chunks(2)
+            .all(|point| point[0] <= 4.0));
+        let area: f64 = triangulation
+            .triangles
+            .chunks(3)
+            .map(|triangle| {
+                let corners: Vec<f64> = triangle
+                    .iter()
+                    .flat_map(|vertex| {
+                        triangulation.coordinates[vertex * 2..vertex * 2 + 2].to_vec()
+                    })
+                    .collect();
+                polygon_area(&corners)
+            })
+            .sum();
+        assert!((area - 12.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_reports_line_of_errors() {
+        let text = "{\"type\": \"Polygon\",\n\"coordinates\": [[[0, 0], [1, 0]]]}";
+        let error = read_geojson(text).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "line 2: expected a ring of 3 positions or more"
+        );
+
+        let error = read_geojson("{\"type\": \"Point\", \"coordinates\": [0, 0]}")
+            .err()
+            .unwrap();
+        assert_eq!(error.message, "unsupported type 'Point'");
+
+        let error = read_geojson("{\"type\":\n[1, 2]}").err().unwrap();
+        assert_eq!(error.line, 2);
+        let error = read_geojson("{\"type\": \"Polygon\",\n\"coordinates\": [[")
+            .err()
+            .unwrap();
+        assert_eq!(error.line, 0);
+    }
+}
+
+#[cfg(test)]
+mod triangulate {
+    use super::*;
+
+    #[test]
+    fn test_rejects_degenerate_input() {
+        let geometry = read_wkt("POLYGON ((0 0, 1 0, 1 0, 0 0))").unwrap();
+        assert_eq!(
+            geometry.triangulate().err().unwrap(),
+            "expected a ring of 3 distinct positions or more"
+        );
+        let geometry = read_wkt("MULTIPOINT (0 0, 1 1, 2 2, 1 1)").unwrap();
+        assert_eq!(
+            geometry.triangulate().err().unwrap(),
+            "expected 3 positions or more, not all on a line"
+        );
+    }
+
+    #[test]
+    fn test_drops_repeated_positions() {
+        let geometry = read_wkt("POLYGON ((0 0, 1 0, 1 0, 0 1, 0 0))").unwrap();
+        let triangulation = geometry.triangulate().unwrap().export();
+        assert_eq!(triangulation.triangles.len() / 3, 1);
+    }
+}
+
+#[cfg(test)]
+mod read_wkt {
+    use super::*;
+
+    #[test]
+    fn test_multipolygon() {
+        let text =
+            "MULTIPOLYGON Z (((0 0 1, 2 0 1, 2 2 1, 0 0 1)),\n((3 0 0, 5 0 0, 4 2 0, 3 0 0)))";
+        let geometry = read_wkt(text).unwrap();
+        assert_eq!(geometry.polygons.len(), 2);
+        assert_eq!(geometry.polygons[1][0], vec![3.0, 0.0, 5.0, 0.0, 4.0, 2.0]);
+
+        let triangulation = geometry.triangulate().unwrap().export();
+        assert_eq!(triangulation.triangles.len() / 3, 2);
+    }
+
+    #[test]
+    fn test_multipoint_and_collection() {
+        let geometry = read_wkt("MULTIPOINT ((1 2), (3 4))").unwrap();
+        assert_eq!(geometry.points, vec![1.0, 2.0, 3.0, 4.0]);
+
+        let text = "GEOMETRYCOLLECTION (MULTIPOINT (1 2, 3 4), POLYGON EMPTY)";
+        let geometry = read_wkt(text).unwrap();
+        assert_eq!(geometry.points, vec![1.0, 2.0, 3.0, 4.0]);
+        assert!(geometry.polygons.is_empty());
+    }
+
+    #[test]
+    fn test_reports_line_of_errors() {
+        let error = read_wkt("POLYGON ((0 0, 1 0,\n1 x, 0 0))").err().unwrap();
+        assert_eq!(error.to_string(), "line 2: invalid number 'x'");
+        assert_eq!(read_wkt("POLYGON ((0 0, 1 0, 1 1)").err().unwrap().line, 0);
+    }
+}
+
+#[cfg(test)]
+mod write_geo {
+    use super::*;
+
+    fn triangles() -> Triangulation {
+        let mut triangulation = Triangulation::from(
+            vec![0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0],
+            vec![0, 1, 2, 0, 2, 3],
+        );
+        triangulation.attributes = vec![1.0, 2.5];
+        triangulation
+    }
+
+    #[test]
+    fn test_geojson() {
+        let text = write_geojson(&triangles(), &[("error", &[0.25, 0.5])]);
+        assert!(text.starts_with("{\"type\":\"FeatureCollection\",\"features\":["));
+        assert!(text.contains("\"coordinates\":[[[0,0],[1,1],[0,1],[0,0]]]"));
+        assert!(text.contains("\"properties\":{\"attribute\":2.5,\"error\":0.5}"));
+
+        #[cfg(feature = "geojson")]
+        assert_eq!(read_geojson(&text).unwrap().polygons.len(), 2);
+    }
+
+    #[test]
+    fn test_wkt() {
+        let mut triangulation = triangles();
+        assert_eq!(
+            write_wkt_multipolygon(&triangulation),
+            "MULTIPOLYGON (((0 0, 1 0, 1 1, 0 0)), ((0 0, 1 1, 0 1, 0 0)))"
+        );
+        triangulation.z = vec![1.0, 2.0, 3.0, 4.0];
+        assert!(
+            write_wkt_tin(&triangulation).starts_with("TIN Z (((0 0 1, 1 0 2, 1 1 3, 0 0 1)), ")
+        );
+
+        let geometry = read_wkt(&write_wkt_tin(&triangulation)).unwrap();
+        assert_eq!(geometry.polygons[1][0], vec![0.0, 0.0, 1.0, 1.0, 0.0, 1.0]);
+        assert_eq!(
+            write_wkt_tin(&Triangulation::from(Vec::new(), Vec::new())),
+            "TIN EMPTY"
+        );
+    }
+}
@@ -0,0 +1,535 @@
+use crate::parse_error::*;
+use crate::triangulation::*;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/* Gmsh MSH 4.1 files, in ASCII.
+
+    - $Entities: points, curves and surfaces, each with its physical groups.
+    - $Nodes: blocks of nodes by entity, tags first, then coordinates.
+    - $Elements: blocks of elements by entity and type, 2-node lines
+    and 3-node triangles being the ones read, others skipped.
+
+The physical group of a curve is the boundary marker of its lines, and
+that of a surface the regional attribute of its triangles. Entities
+without a group give 0. Physical names and other sections are ignored.
+
+Written files hold a curve per boundary marker and a surface per
+attribute, with every node in the first surface. Attributes are used as
+physical tags, so only whole positive ones are kept.  */
+
+const LINE: usize = 1;
+const TRIANGLE: usize = 2;
+
+/* Lines of a section body, ended by its $End line */
+struct Section<'a> {
+    name: &'a str,
+    reader: LineReader<'a>,
+}
+
+/**
+ * Splits the file into its sections, from `$Name` to `$EndName`.
+ */
+fn sections(text: &str) -> Result<Vec<Section<'_>>, ParseError> {
+    let mut sections: Vec<Section> = Vec::new();
+    let mut current: Option<Section> = None;
+    for (index, line) in text.lines().enumerate() {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        if tokens.is_empty() {
+            continue;
+        }
+        let line_number = index + 1;
+        match current.take() {
+            Some(mut section) => {
+                if tokens[0] == format!("$End{}", section.name) {
+                    section.reader.section_end = Some((line_number, section.name));
+                    sections.push(section);
+                } else {
+                    section.reader.lines.push((line_number, tokens));
+                    current = Some(section);
+                }
+            }
+            None => {
+                if !tokens[0].starts_with('$') {
+                    return Err(ParseError::new(line_number, "expected a section"));
+                }
+                current = Some(Section {
+                    name: &tokens[0][1..],
+                    reader: LineReader::new(Vec::new()),
+                });
+            }
+        }
+    }
+    match current {
+        Some(section) => Err(ParseError::new(
+            0,
+            &format!("expected $End{}", section.name),
+        )),
+        None => Ok(sections),
+    }
+}
+
+/**
+ * Reads a mesh of 2-node lines and 3-node triangles. Vertices on a marked
+ * line take its marker, and clockwise triangles are turned counterclockwise.
+ */
+pub fn read_msh(text: &str) -> Result<Triangulation, ParseError> {
+    let mut sections = sections(text)?;
+    match sections.first_mut() {
+        Some(section) if section.name == "MeshFormat" => {
+            let (line, numbers) = section.reader.numbers(3, "mesh format")?;
+            if numbers[0] != 4.1 {
+                return Err(ParseError::new(
+                    line,
+                    &format!("unsupported version {}", numbers[0]),
+                ));
+            }
+            if numbers[1] != 0.0 {
+                return Err(ParseError::new(line, "only ASCII files are supported"));
+            }
+        }
+        Some(section) => {
+            return Err(ParseError::new(
+                section.reader.section_end.map_or(0, |(line, _)| line),
+                "expected $MeshFormat first",
+            ))
+        }
+        None => return Err(ParseError::new(0, "expected $MeshFormat")),
+    }
+
+    /* first physical group of each entity, by dimension and tag */
+    let mut physical_groups: HashMap<(usize, usize), i32> = HashMap::new();
+    let mut node_indices: HashMap<usize, usize> = HashMap::new();
+    let mut coordinates: Vec<f64> = Vec::new();
+    let mut z: Vec<f64> = Vec::new();
+    let mut triangles: Vec<usize> = Vec::new();
+    let mut attributes: Vec<f64> = Vec::new();
+    let mut boundary_edges: Vec<usize> = Vec::new();
+    let mut boundary_markers: Vec<i32> = Vec::new();
+
+    for section in sections.iter_mut().skip(1) {
+        match section.name {
+            "Entities" => read_entities(section, &mut physical_groups)?,
+            "Nodes" => {
+                let (line, header) = section.reader.numbers(4, "nodes header")?;
+                for _ in 0..LineReader::count(line, header[0])? {
+                    let (line, block) = section.reader.numbers(4, "node block")?;
+                    let size = LineReader::count(line, block[3])?;
+                    let mut tags: Vec<usize> = Vec::new();
+                    for _ in 0..size {
+                        let (line, numbers) = section.reader.numbers(1, "node tag")?;
+                        tags.push(LineReader::count(line, numbers[0])?);
+                    }
+                    for tag in tags {
+                        let (line, numbers) = section.reader.numbers(3, "node coordinates")?;
+                        if node_indices.insert(tag, coordinates.len() / 2).is_some() {
+                            return Err(ParseError::new(
+                                line,
+                                &format!("node {} defined twice", tag),
+                            ));
+                        }
+                        coordinates.push(numbers[0]);
+                        coordinates.push(numbers[1]);
+                        z.push(numbers[2]);
+                    }
+                }
+            }
+            "Elements" => {
+                let (line, header) = section.reader.numbers(4, "elements header")?;
+                for _ in 0..LineReader::count(line, header[0])? {
+                    let (line, block) = section.reader.numbers(4, "element block")?;
+                    let dimension = LineReader::count(line, block[0])?;
+                    let entity = LineReader::count(line, block[1])?;
+                    let element_type = LineReader::count(line, block[2])?;
+                    let group = *physical_groups.get(&(dimension, entity)).unwrap_or(&0);
+                    for _ in 0..LineReader::count(line, block[3])? {
+                        let nodes_size = match element_type {
+                            LINE => 2,
+                            TRIANGLE => 3,
+                            _ => 0,
+                        };
+                        let (line, numbers) = section.reader.numbers(1 + nodes_size, "element")?;
+                        let mut nodes: Vec<usize> = Vec::new();
+                        for number in numbers[1..=nodes_size].iter() {
+                            let tag = LineReader::count(line, *number)?;
+                            match node_indices.get(&tag) {
+                                Some(index) => nodes.push(*index),
+                                None => {
+                                    return Err(ParseError::new(
+                                        line,
+                                        &format!("unknown node {}", tag),
+                                    ))
+                                }
+                            }
+                        }
+                        match element_type {
+                            LINE => {
+                                boundary_edges.extend(nodes);
+                                boundary_markers.push(group);
+                            }
+                            TRIANGLE => {
+                                if double_area(&coordinates, &nodes) < 0.0 {
+                                    nodes.swap(1, 2);
+                                }
+                                triangles.extend(nodes);
+                                attributes.push(group as f64);
+                            }
+                            _ => (),
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    let mut triangulation = Triangulation::from(coordinates, triangles);
+    triangulation.attributes = attributes;
+    for (edge, marker) in boundary_edges.chunks(2).zip(boundary_markers.iter()) {
+        for vertex in edge.iter() {
+            if triangulation.vertex_markers[*vertex] == 0 {
+                triangulation.vertex_markers[*vertex] = *marker;
+            }
+        }
+    }
+    triangulation.boundary_edges = boundary_edges;
+    triangulation.boundary_markers = boundary_markers;
+    if z.iter().any(|elevation| *elevation != 0.0) {
+        triangulation.z = z;
+    }
+    Ok(triangulation)
+}
+
+fn read_entities(
+    section: &mut Section,
+    physical_groups: &mut HashMap<(usize, usize), i32>,
+) -> Result<(), ParseError> {
+    let (line, header) = section.reader.numbers(4, "entities header")?;
+    for (dimension, size) in header.iter().take(4).enumerate() {
+        for _ in 0..LineReader::count(line, *size)? {
+            /* points have a position, the others a bounding box */
+            let groups_position = if dimension == 0 { 4 } else { 7 };
+            let (line, numbers) = section.reader.numbers(groups_position + 1, "entity")?;
+            let tag = LineReader::count(line, numbers[0])?;
+            let groups_size = LineReader::count(line, numbers[groups_position])?;
+            if groups_size > 0 {
+                match numbers.get(groups_position + 1) {
+                    Some(group) => {
+                        physical_groups.insert((dimension, tag), group.abs() as i32);
+                    }
+                    None => return Err(ParseError::new(line, "expected a physical tag")),
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn double_area(coordinates: &[f64], triangle: &[usize]) -> f64 {
+    let (x1, y1) = (
+        coordinates[triangle[0] * 2],
+        coordinates[triangle[0] * 2 + 1],
+    );
+    let (x2, y2) = (
+        coordinates[triangle[1] * 2],
+        coordinates[triangle[1] * 2 + 1],
+    );
+    let (x3, y3) = (
+        coordinates[triangle[2] * 2],
+        coordinates[triangle[2] * 2 + 1],
+    );
+    (x2 - x1) * (y3 - y1) - (y2 - y1) * (x3 - x1)
+}
+
+pub fn write_msh(triangulation: &Triangulation) -> String {
+    let vertices_size = triangulation.coordinates.len() / 2;
+    let triangles_size = triangulation.triangles.len() / 3;
+    let boundary_edges_size = triangulation.boundary_edges.len() / 2;
+
+    /* entities in order of first appearance, at least one surface to hold the nodes */
+    let mut curves: Vec<i32> = Vec::new();
+    for index in 0..boundary_edges_size {
+        let marker = *triangulation.boundary_markers.get(index).unwrap_or(&0);
+        if !curves.contains(&marker) {
+            curves.push(marker);
+        }
+    }
+    let mut surfaces: Vec<f64> = Vec::new();
+    for index in 0..triangles_size {
+        let attribute = *triangulation.attributes.get(index).unwrap_or(&0.0);
+        if !surfaces.contains(&attribute) {
+            surfaces.push(attribute);
+        }
+    }
+    if surfaces.is_empty() {
+        surfaces.push(0.0);
+    }
+
+    let mut text = String::new();
+    writeln!(text, "$MeshFormat\n4.1 0 8\n$EndMeshFormat").unwrap();
+
+    writeln!(text, "$Entities").unwrap();
+    writeln!(text, "0 {} {} 0", curves.len(), surfaces.len()).unwrap();
+    for (index, marker) in curves.iter().enumerate() {
+        let vertices = (0..boundary_edges_size)
+            .filter(|edge| triangulation.boundary_markers.get(*edge).unwrap_or(&0) == marker)
+            .flat_map(|edge| triangulation.boundary_edges[edge * 2..edge * 2 + 2].to_vec());
+        write!(text, "{} ", index + 1).unwrap();
+        write_bounding_box(&mut text, triangulation, vertices);
+        write_physical_group(&mut text, *marker as f64);
+        writeln!(text, " 0").unwrap();
+    }
+    for (index, attribute) in surfaces.iter().enumerate() {
+        write!(text, "{} ", index + 1).unwrap();
+        if index == 0 {
+            write_bounding_box(&mut text, triangulation, 0..vertices_size);
+        } else {
+            let vertices = (0..triangles_size)
+                .filter(|triangle| triangulation.attributes[*triangle] == *attribute)
+                .flat_map(|triangle| {
+                    triangulation.triangles[triangle * 3..triangle * 3 + 3].to_vec()
+                });
+            write_bounding_box(&mut text, triangulation, vertices);
+        }
+        write_physical_group(&mut text, *attribute);
+        writeln!(text, " 0").unwrap();
+    }
+    writeln!(text, "$EndEntities").unwrap();
+
+    writeln!(text, "$Nodes").unwrap();
+    if vertices_size == 0 {
+        writeln!(text, "0 0 0 0").unwrap();
+    } else {
+        writeln!(text, "1 {} 1 {}", vertices_size, vertices_size).unwrap();
+        writeln!(text, "2 1 0 {}", vertices_size).unwrap();
+        for index in 0..vertices_size {
+            writeln!(text, "{}", index + 1).unwrap();
+        }
+        for index in 0..vertices_size {
+            writeln!(
+                text,
+                "{} {} {}",
+                triangulation.coordinates[index * 2],
+                triangulation.coordinates[index * 2 + 1],
+                triangulation.z.get(index).unwrap_or(&0.0)
+            )
+            .unwrap();
+        }
+    }
+    writeln!(text, "$EndNodes").unwrap();
+
+    /* one block per entity holding elements, numbered in the order written */
+    let mut blocks: Vec<(usize, usize, usize, Vec<usize>)> = Vec::new();
+    for (index, marker) in curves.iter().enumerate() {
+        let edges: Vec<usize> = (0..boundary_edges_size)
+            .filter(|edge| triangulation.boundary_markers.get(*edge).unwrap_or(&0) == marker)
+            .collect();
+        blocks.push((1, index + 1, LINE, edges));
+    }
+    for (index, attribute) in surfaces.iter().enumerate() {
+        let triangles: Vec<usize> = (0..triangles_size)
+            .filter(|triangle| triangulation.attributes.get(*triangle).unwrap_or(&0.0) == attribute)
+            .collect();
+        if !triangles.is_empty() {
+            blocks.push((2, index + 1, TRIANGLE, triangles));
+        }
+    }
+    let elements_size = boundary_edges_size + triangles_size;
+    writeln!(text, "$Elements").unwrap();
+    writeln!(
+        text,
+        "{} {} {} {}",
+        blocks.len(),
+        elements_size,
+        elements_size.min(1),
+        elements_size
+    )
+    .unwrap();
+    let mut tag = 0;
+    for (dimension, entity, element_type, elements) in blocks.iter() {
+        writeln!(
+            text,
+            "{} {} {} {}",
+            dimension,
+            entity,
+            element_type,
+            elements.len()
+        )
+        .unwrap();
+        let (nodes, nodes_size) = if *element_type == LINE {
+            (&triangulation.boundary_edges, 2)
+        } else {
+            (&triangulation.triangles, 3)
+        };
+        for element in elements.iter() {
+            tag += 1;
+            write!(text, "{}", tag).unwrap();
+            for node in nodes[element * nodes_size..(element + 1) * nodes_size].iter() {
+                write!(text, " {}", node + 1).unwrap();
+            }
+            writeln!(text).unwrap();
+        }
+    }
+    writeln!(text, "$EndElements").unwrap();
+    text
+}
+
+fn write_bounding_box<I: Iterator<Item = usize>>(
+    text: &mut String,
+    triangulation: &Triangulation,
+    vertices: I,
+) {
+    let mut min = [f64::INFINITY; 3];
+    let mut max = [f64::NEG_INFINITY; 3];
+    for vertex in vertices {
+        let position = [
+            triangulation.coordinates[vertex * 2],
+            triangulation.coordinates[vertex * 2 + 1],
+            *triangulation.z.get(vertex).unwrap_or(&0.0),
+        ];
+        for axis in 0..3 {
+            min[axis] = min[axis].min(position[axis]);
+            max[axis] = max[axis].max(position[axis]);
+        }
+    }
+    if min[0] > max[0] {
+        min = [0.0; 3];
+        max = [0.0; 3];
+    }
+    write!(
+        text,
+        "{} {} {} {} {} {}",
+        min[0], min[1], min[2], max[0], max[1], max[2]
+    )
+    .unwrap();
+}
+
+/* whole positive numbers become a physical group, others none */
+fn write_physical_group(text: &mut String, group: f64) {
+    if group >= 1.0 && group.fract() == 0.0 {
+        write!(text, " 1 {}", group).unwrap();
+    } else {
+        write!(text, " 0").unwrap();
+    }
+}
+
+#[cfg(test)]
+mod read_msh {
+    use super::*;
+
+    /* unit square split into two triangles, the second one clockwise */
+    const SQUARE: &str = "$MeshFormat
+4.1 0 8
+$EndMeshFormat
+$PhysicalNames
+2
+1 5 \"bottom wall\"
+2 9 \"fluid\"
+$EndPhysicalNames
+$Entities
+0 2 1 0
+1 0 0 0 1 0 0 1 5 0
+2 0 0 0 1 1 0 0 0
+3 0 0 0 1 1 0 1 9 0
+$EndEntities
+$Nodes
+1 4 10 40
+2 3 0 4
+10
+20
+30
+40
+0 0 0
+1 0 0
+1 1 0
+0 1 0
+$EndNodes
+$Elements
+3 4 1 4
+1 1 1 1
+1 10 20
+1 2 1 1
+2 30 40
+2 3 2 2
+3 10 20 30
+4 10 40 30
+$EndElements
+";
+
+    #[test]
+    fn test_reads_mesh() {
+        let triangulation = read_msh(SQUARE).unwrap();
+        assert_eq!(
+            triangulation.coordinates,
+            vec![0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0]
+        );
+        assert_eq!(triangulation.triangles, vec![0, 1, 2, 0, 2, 3]);
+        assert_eq!(triangulation.attributes, vec![9.0, 9.0]);
+        assert_eq!(triangulation.boundary_edges, vec![0, 1, 2, 3]);
+        assert_eq!(triangulation.boundary_markers, vec![5, 0]);
+        assert_eq!(triangulation.vertex_markers, vec![5, 5, 0, 0]);
+        assert!(triangulation.z.is_empty());
+    }
+
+    #[test]
+    fn test_reports_line_of_errors() {
+        let error = read_msh(&SQUARE.replace("4 10 40 30", "4 10 50 30"))
+            .err()
+            .unwrap();
+        assert_eq!(error.to_string(), "line 35: unknown node 50");
+
+        let error = read_msh(&SQUARE.replace("4.1 0 8", "2.2 0 8"))
+            .err()
+            .unwrap();
+        assert_eq!(error.line, 2);
+
+        let error = read_msh(&SQUARE.replace("$EndElements\n", ""))
+            .err()
+            .unwrap();
+        assert_eq!(error.line, 0);
+    }
+}
+
+#[cfg(test)]
+mod round_trip {
+    use super::*;
+    use crate::triangulator::*;
+    use crate::vertex::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_mesh_round_trip() {
+        let mut triangulator = Triangulator::from_coordinates(vec![
+            0.0, 0.0, 4.0, 0.0, 4.0, 2.0, 0.0, 2.0, 2.0, 0.0, 2.0, 2.0, 1.1, 0.9, 3.1, 1.2,
+        ]);
+        triangulator.triangulate();
+        triangulator.insert_marked_segment(
+            Rc::new(Vertex::new(2.0, 0.0)),
+            Rc::new(Vertex::new(2.0, 2.0)),
+            4,
+        );
+        triangulator.insert_region(Rc::new(Vertex::new(1.0, 1.0)), 1.0, None);
+        triangulator.insert_region(Rc::new(Vertex::new(3.0, 1.0)), 2.0, None);
+        let triangulation = triangulator.export_preserving_order();
+
+        let read = read_msh(&write_msh(&triangulation)).unwrap();
+        assert_eq!(read.coordinates, triangulation.coordinates);
+        assert_eq!(read.triangles.len(), triangulation.triangles.len());
+        let mut attributes = read.attributes.clone();
+        attributes.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let mut expected = triangulation.attributes.clone();
+        expected.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(attributes, expected);
+        assert_eq!(
+            read.boundary_markers
+                .iter()
+                .filter(|marker| **marker == 4)
+                .count(),
+            triangulation
+                .boundary_markers
+                .iter()
+                .filter(|marker| **marker == 4)
+                .count()
+        );
+    }
+}
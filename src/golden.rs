@@ -0,0 +1,138 @@
+use crate::triangulation::*;
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/* Golden files: expected triangulations kept as text, in the Display format,
+for regression tests.
+
+    - Coordinates must match in order, within the tolerance.
+    - Triangles may come in any order and start at any corner, as the
+    triangulator does not keep them in a set order, but must keep their
+    orientation.
+
+Setting the UPDATE_GOLDEN environment variable writes the triangulation to
+the file instead of comparing, to accept an intended change.  */
+
+pub fn compare_golden(
+    triangulation: &Triangulation,
+    path: &Path,
+    tolerance: f64,
+) -> Result<(), String> {
+    if env::var_os("UPDATE_GOLDEN").is_some() {
+        return fs::write(path, triangulation.to_string())
+            .map_err(|error| format!("{}: {}", path.display(), error));
+    }
+
+    let text =
+        fs::read_to_string(path).map_err(|error| format!("{}: {}", path.display(), error))?;
+    let expected: Triangulation = text
+        .parse()
+        .map_err(|error| format!("{}: {}", path.display(), error))?;
+
+    let (actual_size, expected_size) = (
+        triangulation.coordinates.len() / 2,
+        expected.coordinates.len() / 2,
+    );
+    if actual_size != expected_size {
+        return Err(format!(
+            "expected {} vertices, found {}",
+            expected_size, actual_size
+        ));
+    }
+    for index in 0..actual_size {
+        let actual = &triangulation.coordinates[index * 2..index * 2 + 2];
+        let wanted = &expected.coordinates[index * 2..index * 2 + 2];
+        if (actual[0] - wanted[0]).abs() > tolerance || (actual[1] - wanted[1]).abs() > tolerance {
+            return Err(format!(
+                "vertex {}: expected ({}, {}), found ({}, {})",
+                index, wanted[0], wanted[1], actual[0], actual[1]
+            ));
+        }
+    }
+
+    let actual_triangles = normalized_triangles(&triangulation.triangles);
+    let expected_triangles = normalized_triangles(&expected.triangles);
+    let mut missing: Vec<&[usize; 3]> = expected_triangles.difference(&actual_triangles).collect();
+    let mut unexpected: Vec<&[usize; 3]> =
+        actual_triangles.difference(&expected_triangles).collect();
+    if missing.is_empty() && unexpected.is_empty() {
+        return Ok(());
+    }
+    missing.sort();
+    unexpected.sort();
+    Err(format!(
+        "missing triangles {:?}, unexpected triangles {:?}",
+        missing, unexpected
+    ))
+}
+
+/**
+ * Panics with the first difference found, for use within tests.
+ */
+pub fn assert_golden(triangulation: &Triangulation, path: &Path, tolerance: f64) {
+    if let Err(message) = compare_golden(triangulation, path, tolerance) {
+        panic!("golden file {} differs: {}", path.display(), message);
+    }
+}
+
+/* triangles rotated to start at their lowest index */
+fn normalized_triangles(triangles: &[usize]) -> HashSet<[usize; 3]> {
+    triangles
+        .chunks(3)
+        .map(|triangle| {
+            let first = (0..3).min_by_key(|corner| triangle[*corner]).unwrap();
+            [
+                triangle[first],
+                triangle[(first + 1) % 3],
+                triangle[(first + 2) % 3],
+            ]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod compare_golden {
+    use super::*;
+    use crate::triangulator::*;
+
+    fn golden_path(name: &str) -> std::path::PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("golden")
+            .join(name)
+    }
+
+    #[test]
+    fn test_square_with_center() {
+        let mut triangulator =
+            Triangulator::from_coordinates(vec![0.0, 0.0, 2.0, 0.0, 2.0, 2.0, 0.0, 2.0, 1.0, 1.0]);
+        triangulator.triangulate();
+        assert_golden(
+            &triangulator.export(),
+            &golden_path("square_with_center.txt"),
+            1e-12,
+        );
+    }
+
+    #[test]
+    fn test_reports_differences() {
+        let path = golden_path("square_with_center.txt");
+        let flipped = Triangulation::from(
+            vec![0.0, 0.0, 0.0, 2.0, 1.0, 1.0, 2.0, 0.0, 2.0, 2.0],
+            vec![0, 3, 2, 3, 4, 2, 4, 1, 2, 1, 2, 0],
+        );
+        let message = compare_golden(&flipped, &path, 1e-12).err().unwrap();
+        assert_eq!(
+            message,
+            "missing triangles [[0, 2, 1]], unexpected triangles [[0, 1, 2]]"
+        );
+
+        let moved = Triangulation::from(vec![0.0, 0.0, 0.0, 2.0, 1.0, 1.5], Vec::new());
+        assert!(compare_golden(&moved, &path, 1e-12)
+            .err()
+            .unwrap()
+            .starts_with("expected 5 vertices"));
+    }
+}
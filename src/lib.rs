@@ -1,7 +1,27 @@
 mod vertex;
+mod obj_format;
 mod orientation;
+mod parse_error;
+mod ply_format;
+mod polygon;
+mod proximity;
+mod snapshot;
+mod svg_format;
+mod alpha_shape;
 mod continence;
+mod contour;
+mod convex_hull;
+mod geo_format;
+mod golden;
+mod gmsh_format;
+mod location;
 mod triangle;
+mod triangle_format;
 mod triangulation;
+mod union_find;
+mod voronoi;
+mod vtk_format;
+#[cfg(feature = "wasm")]
+mod wasm;
 
 mod triangulator;
@@ -0,0 +1,47 @@
+use crate::triangle::*;
+use crate::vertex::*;
+use std::rc::Rc;
+
+/* Where a point falls in the triangulation.
+
+- Inside a solid triangle.
+- On an edge, given as the directed edge of the solid triangle
+holding it, in counterclockwise order.
+- On a vertex of the triangulation.
+- Outside the triangulated domain, beyond the convex hull or in a
+hole, with the nearest boundary edge, as held by its ghost triangle. */
+
+pub enum Location {
+    Inside(Rc<Triangle>),
+    OnEdge(Rc<Triangle>, Rc<Vertex>, Rc<Vertex>),
+    OnVertex(Rc<Vertex>),
+    Outside(Rc<Vertex>, Rc<Vertex>),
+}
+
+/**
+ * Euclidean distance from the point to the closed segment (v1, v2).
+ */
+pub fn segment_distance(v1: &Vertex, v2: &Vertex, point: &Vertex) -> f64 {
+    let (dx, dy) = (v2.x - v1.x, v2.y - v1.y);
+    let squared_length = dx.powi(2) + dy.powi(2);
+    let ratio = if squared_length == 0.0 {
+        0.0
+    } else {
+        (((point.x - v1.x) * dx + (point.y - v1.y) * dy) / squared_length).clamp(0.0, 1.0)
+    };
+    (point.x - v1.x - ratio * dx).hypot(point.y - v1.y - ratio * dy)
+}
+
+#[cfg(test)]
+mod segment_distance {
+    use super::*;
+
+    #[test]
+    fn test_segment_distance() {
+        let v1 = Vertex::new(0.0, 0.0);
+        let v2 = Vertex::new(2.0, 0.0);
+        assert_eq!(segment_distance(&v1, &v2, &Vertex::new(1.0, 3.0)), 3.0);
+        assert_eq!(segment_distance(&v1, &v2, &Vertex::new(5.0, 4.0)), 5.0);
+        assert_eq!(segment_distance(&v1, &v1, &Vertex::new(3.0, 4.0)), 5.0);
+    }
+}
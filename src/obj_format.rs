@@ -0,0 +1,49 @@
+use crate::triangulation::*;
+use std::fmt::Write;
+
+/* Wavefront OBJ, holding vertices as "v x y z" lines and triangles as
+"f i j k" lines, counting vertices from 1. Vertices are lifted by their
+elevation, or lie at z = 0 without one.  */
+
+pub fn write_obj(triangulation: &Triangulation) -> String {
+    let mut text = String::new();
+    for index in 0..triangulation.coordinates.len() / 2 {
+        writeln!(
+            text,
+            "v {} {} {}",
+            triangulation.coordinates[index * 2],
+            triangulation.coordinates[index * 2 + 1],
+            triangulation.z.get(index).unwrap_or(&0.0)
+        )
+        .unwrap();
+    }
+    for triangle in triangulation.triangles.chunks(3) {
+        writeln!(
+            text,
+            "f {} {} {}",
+            triangle[0] + 1,
+            triangle[1] + 1,
+            triangle[2] + 1
+        )
+        .unwrap();
+    }
+    text
+}
+
+#[cfg(test)]
+mod write_obj {
+    use super::*;
+
+    #[test]
+    fn test_writes_vertices_and_faces() {
+        let mut triangulation =
+            Triangulation::from(vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0], vec![0, 1, 2]);
+        assert_eq!(
+            write_obj(&triangulation),
+            "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n"
+        );
+
+        triangulation.z = vec![1.0, 2.0, 3.5];
+        assert!(write_obj(&triangulation).starts_with("v 0 0 1\nv 1 0 2\nv 0 1 3.5\n"));
+    }
+}
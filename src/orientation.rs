@@ -1,7 +1,6 @@
-extern crate nalgebra;
 use crate::vertex::*;
 
-use nalgebra::Matrix3;
+use num::{BigRational, Signed};
 
 #[derive(PartialEq, Debug)]
 pub enum Orientation {
@@ -13,21 +12,37 @@ pub enum Orientation {
 /**
  * Checks whether Vertices a, b and c are in counterclockwise order, 
  * in the circumcircle they define.
+ *
+ * As for in_circle, the determinant is evaluated again in exact rational
+ * arithmetic when rounding could change its sign.
  */
 pub fn orient_2d(a: &Vertex, b: &Vertex, c: &Vertex) -> Orientation {
-    let matrix = Matrix3::new(
-        a.x, a.y, 1.0, 
-        b.x, b.y, 1.0, 
-        c.x, c.y, 1.0,
-    );
-    let det = matrix.determinant();
-    
-    if det > 0.0 {
-        return Orientation::Counterclockwise;
-    } else if det < 0.0 {
-        return Orientation::Clockwise;
+    let left = (a.x - c.x) * (b.y - c.y);
+    let right = (a.y - c.y) * (b.x - c.x);
+    let det = left - right;
+    let epsilon = f64::EPSILON / 2.0;
+    let error_bound = (3.0 + 16.0 * epsilon) * epsilon * (left.abs() + right.abs());
+
+    if det > error_bound {
+        Orientation::Counterclockwise
+    } else if det < -error_bound {
+        Orientation::Clockwise
+    } else {
+        exact_orient_2d(a, b, c)
+    }
+}
+
+fn exact_orient_2d(a: &Vertex, b: &Vertex, c: &Vertex) -> Orientation {
+    let exact = |value: f64| BigRational::from_float(value).unwrap();
+    let (cx, cy) = (exact(c.x), exact(c.y));
+    let det = (exact(a.x) - &cx) * (exact(b.y) - &cy) - (exact(a.y) - &cy) * (exact(b.x) - &cx);
+
+    if det.is_positive() {
+        Orientation::Counterclockwise
+    } else if det.is_negative() {
+        Orientation::Clockwise
     } else {
-        return Orientation::Colinear;
+        Orientation::Colinear
     }
 }
 
@@ -58,4 +73,12 @@ mod orient_2d {
         let p3 = Vertex::new(2.0, 2.0);
         assert_eq!(orient_2d(&p1, &p2, &p3), Orientation::Colinear);
     }
+    #[test]
+    fn test_counterclockwise_nearly_colinear() {
+        /* rounding gives the floating point determinant the opposite sign */
+        let p1 = Vertex::new(89.2, 52.5);
+        let p2 = Vertex::new(96.59, 56.23);
+        let p3 = Vertex::new(103.98, 59.96);
+        assert_eq!(orient_2d(&p1, &p2, &p3), Orientation::Counterclockwise);
+    }
 }
@@ -0,0 +1,120 @@
+use std::error::Error;
+use std::fmt;
+
+/* Error raised while reading a mesh file, pointing at the offending line */
+
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    /* 1-based line number, 0 when the input ends too early */
+    pub line: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, message: &str) -> Self {
+        Self {
+            line,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            write!(formatter, "unexpected end of input: {}", self.message)
+        } else {
+            write!(formatter, "line {}: {}", self.line, self.message)
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/* Lines holding whitespace separated tokens, with their number, read one at a
+time as numbers. Lines running out is reported at the end of the enclosing
+section, if any, or else at the end of input. */
+pub(crate) struct LineReader<'a> {
+    pub(crate) lines: Vec<(usize, Vec<&'a str>)>,
+    pub(crate) position: usize,
+    /* line ending the section and its name */
+    pub(crate) section_end: Option<(usize, &'a str)>,
+}
+
+impl<'a> LineReader<'a> {
+    pub(crate) fn new(lines: Vec<(usize, Vec<&'a str>)>) -> Self {
+        Self {
+            lines,
+            position: 0,
+            section_end: None,
+        }
+    }
+
+    pub(crate) fn next_line(
+        &mut self,
+        expected: &str,
+    ) -> Result<(usize, Vec<&'a str>), ParseError> {
+        match self.lines.get(self.position) {
+            Some((line, tokens)) => {
+                self.position += 1;
+                Ok((*line, tokens.clone()))
+            }
+            None => Err(match self.section_end {
+                Some((line, name)) => ParseError::new(
+                    line,
+                    &format!("expected {} before the end of {}", expected, name),
+                ),
+                None => ParseError::new(0, &format!("expected {}", expected)),
+            }),
+        }
+    }
+
+    pub(crate) fn has_next_line(&self) -> bool {
+        self.position < self.lines.len()
+    }
+
+    /**
+     * Reads a line of numbers, at least `size` of them.
+     */
+    pub(crate) fn numbers(
+        &mut self,
+        size: usize,
+        expected: &str,
+    ) -> Result<(usize, Vec<f64>), ParseError> {
+        let (line, tokens) = self.next_line(expected)?;
+        if tokens.len() < size {
+            return Err(ParseError::new(
+                line,
+                &format!(
+                    "expected {} numbers for {}, found {}",
+                    size,
+                    expected,
+                    tokens.len()
+                ),
+            ));
+        }
+        let mut numbers: Vec<f64> = Vec::new();
+        for token in tokens {
+            match token.parse::<f64>() {
+                Ok(number) => numbers.push(number),
+                Err(_) => {
+                    return Err(ParseError::new(
+                        line,
+                        &format!("invalid number '{}'", token),
+                    ))
+                }
+            }
+        }
+        Ok((line, numbers))
+    }
+
+    pub(crate) fn count(line: usize, number: f64) -> Result<usize, ParseError> {
+        if number < 0.0 || number.fract() != 0.0 {
+            return Err(ParseError::new(
+                line,
+                &format!("expected a count, found {}", number),
+            ));
+        }
+        Ok(number as usize)
+    }
+}
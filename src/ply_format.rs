@@ -0,0 +1,156 @@
+use crate::triangulation::*;
+use std::fmt::Write;
+
+/* Stanford PLY, as ASCII or little endian binary.
+
+    - Vertices hold x, y and z as doubles, z being the elevation or 0
+    without one, followed by the extra vertex properties.
+    - Faces hold their vertex indices as a list of ints, counted by an
+    uchar, followed by the extra face properties.
+
+Extra properties are doubles, given by name with one value per vertex
+or per face.  */
+
+pub type PlyProperty<'a> = (&'a str, &'a [f64]);
+
+pub fn write_ply_ascii(
+    triangulation: &Triangulation,
+    vertex_properties: &[PlyProperty],
+    face_properties: &[PlyProperty],
+) -> String {
+    let mut text = header(triangulation, "ascii", vertex_properties, face_properties);
+    for index in 0..triangulation.coordinates.len() / 2 {
+        let (x, y, z) = position(triangulation, index);
+        write!(text, "{} {} {}", x, y, z).unwrap();
+        for (_, values) in vertex_properties.iter() {
+            write!(text, " {}", values[index]).unwrap();
+        }
+        writeln!(text).unwrap();
+    }
+    for (index, triangle) in triangulation.triangles.chunks(3).enumerate() {
+        write!(text, "3 {} {} {}", triangle[0], triangle[1], triangle[2]).unwrap();
+        for (_, values) in face_properties.iter() {
+            write!(text, " {}", values[index]).unwrap();
+        }
+        writeln!(text).unwrap();
+    }
+    text
+}
+
+pub fn write_ply_binary(
+    triangulation: &Triangulation,
+    vertex_properties: &[PlyProperty],
+    face_properties: &[PlyProperty],
+) -> Vec<u8> {
+    let mut bytes = header(
+        triangulation,
+        "binary_little_endian",
+        vertex_properties,
+        face_properties,
+    )
+    .into_bytes();
+    for index in 0..triangulation.coordinates.len() / 2 {
+        let (x, y, z) = position(triangulation, index);
+        for value in [x, y, z].iter() {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        for (_, values) in vertex_properties.iter() {
+            bytes.extend_from_slice(&values[index].to_le_bytes());
+        }
+    }
+    for (index, triangle) in triangulation.triangles.chunks(3).enumerate() {
+        bytes.push(3);
+        for vertex in triangle.iter() {
+            bytes.extend_from_slice(&(*vertex as i32).to_le_bytes());
+        }
+        for (_, values) in face_properties.iter() {
+            bytes.extend_from_slice(&values[index].to_le_bytes());
+        }
+    }
+    bytes
+}
+
+fn header(
+    triangulation: &Triangulation,
+    format: &str,
+    vertex_properties: &[PlyProperty],
+    face_properties: &[PlyProperty],
+) -> String {
+    let mut text = String::new();
+    writeln!(text, "ply").unwrap();
+    writeln!(text, "format {} 1.0", format).unwrap();
+    writeln!(
+        text,
+        "element vertex {}",
+        triangulation.coordinates.len() / 2
+    )
+    .unwrap();
+    for axis in ["x", "y", "z"].iter() {
+        writeln!(text, "property double {}", axis).unwrap();
+    }
+    for (name, _) in vertex_properties.iter() {
+        writeln!(text, "property double {}", name).unwrap();
+    }
+    writeln!(text, "element face {}", triangulation.triangles.len() / 3).unwrap();
+    writeln!(text, "property list uchar int vertex_indices").unwrap();
+    for (name, _) in face_properties.iter() {
+        writeln!(text, "property double {}", name).unwrap();
+    }
+    writeln!(text, "end_header").unwrap();
+    text
+}
+
+fn position(triangulation: &Triangulation, index: usize) -> (f64, f64, f64) {
+    (
+        triangulation.coordinates[index * 2],
+        triangulation.coordinates[index * 2 + 1],
+        *triangulation.z.get(index).unwrap_or(&0.0),
+    )
+}
+
+#[cfg(test)]
+mod write_ply {
+    use super::*;
+
+    fn triangle() -> Triangulation {
+        Triangulation::from(vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0], vec![0, 1, 2])
+    }
+
+    #[test]
+    fn test_ascii() {
+        let text = write_ply_ascii(
+            &triangle(),
+            &[("value", &[1.0, 2.0, 3.0])],
+            &[("attribute", &[7.0])],
+        );
+        let expected = "ply
+format ascii 1.0
+element vertex 3
+property double x
+property double y
+property double z
+property double value
+element face 1
+property list uchar int vertex_indices
+property double attribute
+end_header
+0 0 0 1
+1 0 0 2
+0 1 0 3
+3 0 1 2 7
+";
+        assert_eq!(text, expected);
+    }
+
+    #[test]
+    fn test_binary() {
+        let bytes = write_ply_binary(&triangle(), &[], &[]);
+        let header_size = header(&triangle(), "binary_little_endian", &[], &[]).len();
+        assert_eq!(bytes.len(), header_size + 3 * 3 * 8 + 1 + 3 * 4);
+
+        let second_x = &bytes[header_size + 3 * 8..header_size + 4 * 8];
+        assert_eq!(second_x, &1.0_f64.to_le_bytes());
+        assert_eq!(bytes[header_size + 9 * 8], 3);
+        assert_eq!(&bytes[bytes.len() - 4..], &2_i32.to_le_bytes());
+    }
+}
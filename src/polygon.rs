@@ -0,0 +1,105 @@
+/**
+ * Signed area of a polygon given by pairs of x,y coordinates.
+ * Positive if the vertices are in counterclockwise order.
+ */
+pub fn polygon_area(polygon: &[f64]) -> f64 {
+    let size = polygon.len() / 2;
+    let mut double_area = 0.0;
+    for index in 0..size {
+        let next = (index + 1) % size;
+        double_area +=
+            polygon[index * 2] * polygon[next * 2 + 1] - polygon[next * 2] * polygon[index * 2 + 1];
+    }
+    double_area / 2.0
+}
+
+/**
+ * Centroid of a polygon given by pairs of x,y coordinates.
+ * Degenerate polygons, with no area, have no centroid.
+ */
+pub fn polygon_centroid(polygon: &[f64]) -> Option<(f64, f64)> {
+    let area = polygon_area(polygon);
+    if area == 0.0 {
+        return None;
+    }
+
+    let size = polygon.len() / 2;
+    let mut x = 0.0;
+    let mut y = 0.0;
+    for index in 0..size {
+        let next = (index + 1) % size;
+        let (x1, y1) = (polygon[index * 2], polygon[index * 2 + 1]);
+        let (x2, y2) = (polygon[next * 2], polygon[next * 2 + 1]);
+        let cross = x1 * y2 - x2 * y1;
+        x += (x1 + x2) * cross;
+        y += (y1 + y2) * cross;
+    }
+    Some((x / (6.0 * area), y / (6.0 * area)))
+}
+
+/**
+ * Whether the point lies inside a polygon given by pairs of x,y coordinates, by
+ * counting the edges crossed by a ray leaving it. Works in either orientation
+ * and for concave polygons. Points on an edge may fall on either side.
+ */
+pub fn polygon_contains(polygon: &[f64], x: f64, y: f64) -> bool {
+    let size = polygon.len() / 2;
+    let mut is_inside = false;
+    for index in 0..size {
+        let next = (index + 1) % size;
+        let (x1, y1) = (polygon[index * 2], polygon[index * 2 + 1]);
+        let (x2, y2) = (polygon[next * 2], polygon[next * 2 + 1]);
+        if (y1 > y) != (y2 > y) && x < x1 + (y - y1) * (x2 - x1) / (y2 - y1) {
+            is_inside = !is_inside;
+        }
+    }
+    is_inside
+}
+
+#[cfg(test)]
+mod polygon_area {
+    use super::*;
+
+    #[test]
+    fn test_counterclockwise_is_positive() {
+        let square = vec![0.0, 0.0, 2.0, 0.0, 2.0, 2.0, 0.0, 2.0];
+        assert_eq!(polygon_area(&square), 4.0);
+    }
+
+    #[test]
+    fn test_clockwise_is_negative() {
+        let square = vec![0.0, 0.0, 0.0, 2.0, 2.0, 2.0, 2.0, 0.0];
+        assert_eq!(polygon_area(&square), -4.0);
+    }
+}
+
+#[cfg(test)]
+mod polygon_centroid {
+    use super::*;
+
+    #[test]
+    fn test_centroid() {
+        let rectangle = vec![0.0, 0.0, 4.0, 0.0, 4.0, 2.0, 0.0, 2.0];
+        assert_eq!(polygon_centroid(&rectangle), Some((2.0, 1.0)));
+    }
+
+    #[test]
+    fn test_degenerate_polygon() {
+        let segment = vec![0.0, 0.0, 4.0, 0.0];
+        assert_eq!(polygon_centroid(&segment), None);
+    }
+}
+
+#[cfg(test)]
+mod polygon_contains {
+    use super::*;
+
+    #[test]
+    fn test_concave_polygon() {
+        let l_shape = vec![0.0, 0.0, 2.0, 0.0, 2.0, 1.0, 1.0, 1.0, 1.0, 2.0, 0.0, 2.0];
+        assert!(polygon_contains(&l_shape, 0.5, 1.5));
+        assert!(polygon_contains(&l_shape, 1.5, 0.5));
+        assert!(!polygon_contains(&l_shape, 1.5, 1.5));
+        assert!(!polygon_contains(&l_shape, 3.0, 0.5));
+    }
+}
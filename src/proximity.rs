@@ -0,0 +1,133 @@
+use crate::triangle::*;
+use crate::union_find::*;
+use crate::vertex::*;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+/* Proximity graphs found among the Delaunay edges.
+
+    - Gabriel graph: edges whose diametral circle holds no other vertex,
+    checked against the opposite vertices of both adjacent triangles.
+    - Relative neighborhood graph: Gabriel edges whose lune, where the
+    circles centered at each end through the other meet, holds no other
+    vertex, checked against the neighbors of both ends.
+    - Euclidean minimum spanning tree: Kruskal over the Delaunay edges.
+
+Each one is a subgraph of the previous. Edges are given as the indices
+of their vertices, in order of arrival, and their length.  */
+
+type Adjacency = HashMap<(Rc<Vertex>, Rc<Vertex>), Rc<Triangle>>;
+
+/**
+ * Solid edges, each listed once, sorted by indices.
+ */
+pub(crate) fn delaunay_edges(
+    adjacency: &Adjacency,
+    vertex_indices: &HashMap<Rc<Vertex>, usize>,
+) -> Vec<(usize, usize, f64)> {
+    edges_where(adjacency, vertex_indices, |_, _| true)
+}
+
+pub(crate) fn gabriel_edges(
+    adjacency: &Adjacency,
+    vertex_indices: &HashMap<Rc<Vertex>, usize>,
+) -> Vec<(usize, usize, f64)> {
+    edges_where(adjacency, vertex_indices, |v1, v2| {
+        is_gabriel(adjacency, v1, v2)
+    })
+}
+
+pub(crate) fn relative_neighborhood_edges(
+    adjacency: &Adjacency,
+    vertex_indices: &HashMap<Rc<Vertex>, usize>,
+) -> Vec<(usize, usize, f64)> {
+    let mut neighbors: HashMap<Rc<Vertex>, Vec<Rc<Vertex>>> = HashMap::new();
+    for (v1, v2) in adjacency.keys() {
+        if !v1.is_ghost && !v2.is_ghost {
+            neighbors
+                .entry(Rc::clone(v1))
+                .or_default()
+                .push(Rc::clone(v2));
+        }
+    }
+
+    edges_where(adjacency, vertex_indices, |v1, v2| {
+        let length = distance(v1, v2);
+        is_gabriel(adjacency, v1, v2)
+            && !neighbors[v1]
+                .iter()
+                .chain(neighbors[v2].iter())
+                .any(|other| distance(other, v1).max(distance(other, v2)) < length)
+    })
+}
+
+/**
+ * Edges of the minimum spanning tree, by increasing length. A forest if the
+ * mesh falls apart.
+ */
+pub(crate) fn minimum_spanning_tree_edges(
+    adjacency: &Adjacency,
+    vertex_indices: &HashMap<Rc<Vertex>, usize>,
+) -> Vec<(usize, usize, f64)> {
+    let mut edges = delaunay_edges(adjacency, vertex_indices);
+    edges.sort_by(|(_, _, l1), (_, _, l2)| l1.partial_cmp(l2).unwrap());
+
+    /* repeated vertices share an entry, so indices may reach past the map size */
+    let size = vertex_indices.values().max().map_or(0, |index| index + 1);
+    let mut components = UnionFind::new(size);
+    let mut tree: Vec<(usize, usize, f64)> = Vec::new();
+    for (index1, index2, length) in edges {
+        if components.union(index1, index2).is_some() {
+            tree.push((index1, index2, length));
+        }
+    }
+    tree
+}
+
+fn edges_where<F>(
+    adjacency: &Adjacency,
+    vertex_indices: &HashMap<Rc<Vertex>, usize>,
+    predicate: F,
+) -> Vec<(usize, usize, f64)>
+where
+    F: Fn(&Rc<Vertex>, &Rc<Vertex>) -> bool,
+{
+    let mut edges: Vec<(usize, usize, f64)> = Vec::new();
+    let mut listed: HashSet<(usize, usize)> = HashSet::new();
+    for (v1, v2) in adjacency.keys() {
+        if v1.is_ghost || v2.is_ghost {
+            continue;
+        }
+        let (index1, index2) = (vertex_indices[v1], vertex_indices[v2]);
+        let pair = (index1.min(index2), index1.max(index2));
+        if listed.insert(pair) && predicate(v1, v2) {
+            edges.push((pair.0, pair.1, distance(v1, v2)));
+        }
+    }
+    edges.sort_by(|(a1, b1, _), (a2, b2, _)| (a1, b1).cmp(&(a2, b2)));
+    edges
+}
+
+/* the opposite vertices of both sides see the edge under an acute angle */
+fn is_gabriel(adjacency: &Adjacency, v1: &Rc<Vertex>, v2: &Rc<Vertex>) -> bool {
+    [(v1, v2), (v2, v1)].iter().all(|(begin, end)| {
+        match adjacency.get(&(Rc::clone(begin), Rc::clone(end))) {
+            Some(triangle) if !triangle.is_ghost() => {
+                let opposite = [&triangle.v1, &triangle.v2, &triangle.v3]
+                    .iter()
+                    .find(|vertex| **vertex != *begin && **vertex != *end)
+                    .map(|vertex| Rc::clone(vertex))
+                    .unwrap();
+                (begin.x - opposite.x) * (end.x - opposite.x)
+                    + (begin.y - opposite.y) * (end.y - opposite.y)
+                    > 0.0
+            }
+            _ => true,
+        }
+    })
+}
+
+fn distance(v1: &Vertex, v2: &Vertex) -> f64 {
+    (v2.x - v1.x).hypot(v2.y - v1.y)
+}
@@ -0,0 +1,97 @@
+use crate::parse_error::*;
+use crate::vertex::*;
+
+/* State of a triangulator, to be stored and restored later, going on with
+insertions and deletions as if never interrupted.
+
+    - Vertices are listed in order of arrival, as indexed on export.
+    - Triangles and constrained segments refer to vertices by index,
+    ghost triangles to GHOST_VERTEX for their vertex at infinity.
+    - Triangles waiting on a vertex in conflict are listed apart, so
+    the vertex is inserted when triangulating again.
+
+A snapshot read back from elsewhere is checked before restoring it. As
+it has no lines, errors point at the offending entry of the list they
+name, counted from 1, and at line 0 for lists of the wrong length.  */
+
+/* Index of the vertex at infinity in the triangles of a snapshot */
+pub const GHOST_VERTEX: usize = usize::MAX;
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TriangulatorSnapshot {
+    pub vertices: Vec<Vertex>,
+    /* triples of vertex indices, solid and ghost triangles alike */
+    pub triangles: Vec<usize>,
+    /* pairs of triangle index and index of the vertex it waits on */
+    pub conflicts: Vec<usize>,
+    /* vertices not inserted yet */
+    pub pending_vertices: Vec<usize>,
+    /* pairs of vertex indices */
+    pub segments: Vec<usize>,
+    pub segment_markers: Vec<i32>,
+    /* boundary marker of each vertex, 0 for unmarked vertices */
+    pub vertex_markers: Vec<i32>,
+    /* pairs of vertex index and value */
+    pub vertex_values: Vec<(usize, f64)>,
+    /* x, y and attribute of each region seed */
+    pub regions: Vec<f64>,
+}
+
+impl TriangulatorSnapshot {
+    pub(crate) fn validate(&self) -> Result<(), ParseError> {
+        let size = self.vertices.len();
+        let groups = [
+            ("triangles", &self.triangles, 3),
+            ("conflicts", &self.conflicts, 2),
+            ("segments", &self.segments, 2),
+        ];
+        for (name, list, group) in groups.iter() {
+            if !list.len().is_multiple_of(*group) {
+                return Err(ParseError::new(
+                    0,
+                    &format!("expected {} by groups of {}", name, group),
+                ));
+            }
+        }
+        if !self.regions.len().is_multiple_of(3) {
+            return Err(ParseError::new(0, "expected regions by groups of 3"));
+        }
+        if self.segment_markers.len() != self.segments.len() / 2 {
+            return Err(ParseError::new(0, "expected a marker for each segment"));
+        }
+        if self.vertex_markers.len() != size {
+            return Err(ParseError::new(0, "expected a marker for each vertex"));
+        }
+
+        let check = |name: &str, entry: usize, index: usize, bound: usize| {
+            if index < bound {
+                Ok(())
+            } else {
+                Err(ParseError::new(
+                    entry + 1,
+                    &format!("index {} out of range in {}", index, name),
+                ))
+            }
+        };
+        for (entry, triangle) in self.triangles.chunks(3).enumerate() {
+            for index in triangle.iter().filter(|index| **index != GHOST_VERTEX) {
+                check("triangles", entry, *index, size)?;
+            }
+        }
+        for (entry, conflict) in self.conflicts.chunks(2).enumerate() {
+            check("conflicts", entry, conflict[0], self.triangles.len() / 3)?;
+            check("conflicts", entry, conflict[1], size)?;
+        }
+        for (entry, index) in self.pending_vertices.iter().enumerate() {
+            check("pending vertices", entry, *index, size)?;
+        }
+        for (entry, segment) in self.segments.chunks(2).enumerate() {
+            check("segments", entry, segment[0], size)?;
+            check("segments", entry, segment[1], size)?;
+        }
+        for (entry, (index, _)) in self.vertex_values.iter().enumerate() {
+            check("vertex values", entry, *index, size)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,389 @@
+use crate::triangle::*;
+use crate::triangulation::*;
+use crate::vertex::*;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::rc::Rc;
+
+/* SVG drawings of meshes, scaled to fit the picture with y pointing up.
+
+    - Triangulation: triangles, boundary edges and vertices.
+    - Triangulator, for debugging: solid triangles, ghost triangles as
+    dashed wedges pointing away from their hull edge, triangles in
+    conflict with the vertex they wait for, circumcircles and
+    constrained segments.
+
+Colors are any SVG paint, such as "none", "#ff0000" or "steelblue", and
+are escaped when written into attributes.  */
+
+pub struct SvgStyle {
+    pub width: f64,
+    pub height: f64,
+    /* blank space around the drawing, in pixels */
+    pub margin: f64,
+    pub stroke_width: f64,
+    pub vertex_radius: f64,
+    pub background: String,
+    pub triangle_fill: String,
+    pub edge_stroke: String,
+    pub vertex_fill: String,
+    pub segment_stroke: String,
+    pub ghost_stroke: String,
+    pub conflict_fill: String,
+    pub conflict_vertex_fill: String,
+    pub circumcircle_stroke: String,
+    pub show_vertices: bool,
+    pub show_ghosts: bool,
+    pub show_circumcircles: bool,
+}
+
+impl Default for SvgStyle {
+    fn default() -> Self {
+        Self {
+            width: 800.0,
+            height: 800.0,
+            margin: 20.0,
+            stroke_width: 1.0,
+            vertex_radius: 2.5,
+            background: "white".to_string(),
+            triangle_fill: "#dde8f0".to_string(),
+            edge_stroke: "#4a6a85".to_string(),
+            vertex_fill: "black".to_string(),
+            segment_stroke: "#d62728".to_string(),
+            ghost_stroke: "#999999".to_string(),
+            conflict_fill: "#ffd27f".to_string(),
+            conflict_vertex_fill: "#ff7f0e".to_string(),
+            circumcircle_stroke: "#2ca02c".to_string(),
+            show_vertices: true,
+            show_ghosts: true,
+            show_circumcircles: false,
+        }
+    }
+}
+
+pub fn write_svg(triangulation: &Triangulation, style: &SvgStyle) -> String {
+    let coordinates = &triangulation.coordinates;
+    let view = View::new(coordinates, style);
+    let point = |index: usize| view.point(coordinates[index * 2], coordinates[index * 2 + 1]);
+
+    let mut text = String::new();
+    view.open(&mut text, style);
+    writeln!(
+        text,
+        "<g fill=\"{}\" stroke=\"{}\" stroke-linejoin=\"round\">",
+        escape(&style.triangle_fill),
+        escape(&style.edge_stroke)
+    )
+    .unwrap();
+    for triangle in triangulation.triangles.chunks(3) {
+        let corners: Vec<(f64, f64)> = triangle.iter().map(|vertex| point(*vertex)).collect();
+        write_polygon(&mut text, &corners, "");
+    }
+    writeln!(text, "</g>").unwrap();
+
+    writeln!(text, "<g stroke=\"{}\">", escape(&style.segment_stroke)).unwrap();
+    for edge in triangulation.boundary_edges.chunks(2) {
+        write_line(&mut text, point(edge[0]), point(edge[1]), "");
+    }
+    writeln!(text, "</g>").unwrap();
+
+    if style.show_vertices {
+        writeln!(text, "<g fill=\"{}\">", escape(&style.vertex_fill)).unwrap();
+        for index in 0..coordinates.len() / 2 {
+            write_circle(&mut text, point(index), style.vertex_radius, "");
+        }
+        writeln!(text, "</g>").unwrap();
+    }
+    writeln!(text, "</svg>").unwrap();
+    text
+}
+
+pub(crate) fn write_debug_svg(
+    vertices: &[Rc<Vertex>],
+    triangles: &HashSet<Rc<Triangle>>,
+    conflict_map: &HashMap<Rc<Triangle>, Rc<Vertex>>,
+    segments: &HashMap<(Rc<Vertex>, Rc<Vertex>), i32>,
+    style: &SvgStyle,
+) -> String {
+    let solid_vertices: Vec<&Vertex> = vertices
+        .iter()
+        .filter(|vertex| !vertex.is_ghost)
+        .map(|vertex| &**vertex)
+        .collect();
+    let coordinates: Vec<f64> = solid_vertices
+        .iter()
+        .flat_map(|vertex| vec![vertex.x, vertex.y])
+        .collect();
+    let view = View::new(&coordinates, style);
+    let point = |vertex: &Vertex| view.point(vertex.x, vertex.y);
+
+    let mut text = String::new();
+    view.open(&mut text, style);
+    /* nothing to draw before triangulating */
+    if solid_vertices.is_empty() {
+        writeln!(text, "</svg>").unwrap();
+        return text;
+    }
+    let mesh_center = Vertex::mean(&solid_vertices);
+
+    /* ghost triangles by their hull edge, the apex away from the mesh */
+    let corners = |triangle: &Triangle| -> Vec<(f64, f64)> {
+        if !triangle.is_ghost() {
+            return vec![
+                point(&triangle.v1),
+                point(&triangle.v2),
+                point(&triangle.v3),
+            ];
+        }
+        let solid: Vec<&Rc<Vertex>> = [&triangle.v1, &triangle.v2, &triangle.v3]
+            .iter()
+            .copied()
+            .filter(|vertex| !vertex.is_ghost)
+            .collect();
+        let (a, b) = match (solid.first(), solid.last()) {
+            (Some(a), Some(b)) => (*a, *b),
+            _ => return Vec::new(),
+        };
+        let (middle_x, middle_y) = ((a.x + b.x) / 2.0, (a.y + b.y) / 2.0);
+        let (mut normal_x, mut normal_y) = (a.y - b.y, b.x - a.x);
+        if normal_x * (mesh_center.x - middle_x) + normal_y * (mesh_center.y - middle_y) > 0.0 {
+            normal_x = -normal_x;
+            normal_y = -normal_y;
+        }
+        let apex = Vertex::new(middle_x + normal_x / 2.0, middle_y + normal_y / 2.0);
+        vec![point(a), point(&apex), point(b)]
+    };
+
+    writeln!(
+        text,
+        "<g fill=\"{}\" stroke=\"{}\" stroke-linejoin=\"round\">",
+        escape(&style.triangle_fill),
+        escape(&style.edge_stroke)
+    )
+    .unwrap();
+    for triangle in triangles.iter().filter(|triangle| !triangle.is_ghost()) {
+        write_polygon(&mut text, &corners(triangle), "");
+    }
+    writeln!(text, "</g>").unwrap();
+
+    if style.show_ghosts {
+        writeln!(
+            text,
+            "<g fill=\"none\" stroke=\"{}\" stroke-dasharray=\"4 3\">",
+            escape(&style.ghost_stroke)
+        )
+        .unwrap();
+        for triangle in triangles.iter().filter(|triangle| triangle.is_ghost()) {
+            write_polygon(&mut text, &corners(triangle), "");
+        }
+        writeln!(text, "</g>").unwrap();
+    }
+
+    writeln!(
+        text,
+        "<g fill=\"{}\" fill-opacity=\"0.6\" stroke=\"{}\">",
+        escape(&style.conflict_fill),
+        escape(&style.conflict_vertex_fill)
+    )
+    .unwrap();
+    for (triangle, vertex) in conflict_map.iter() {
+        let triangle_corners = corners(triangle);
+        write_polygon(&mut text, &triangle_corners, "");
+        let center = triangle_corners.iter().fold((0.0, 0.0), |sum, corner| {
+            (sum.0 + corner.0 / 3.0, sum.1 + corner.1 / 3.0)
+        });
+        write_line(
+            &mut text,
+            center,
+            point(vertex),
+            " stroke-dasharray=\"2 2\"",
+        );
+    }
+    writeln!(text, "</g>").unwrap();
+
+    if style.show_circumcircles {
+        writeln!(
+            text,
+            "<g fill=\"none\" stroke=\"{}\">",
+            escape(&style.circumcircle_stroke)
+        )
+        .unwrap();
+        for triangle in triangles.iter().filter(|triangle| !triangle.is_ghost()) {
+            let radius = triangle.circumradius() * view.scale;
+            if radius.is_finite() {
+                write_circle(&mut text, point(&triangle.circumcenter()), radius, "");
+            }
+        }
+        writeln!(text, "</g>").unwrap();
+    }
+
+    writeln!(
+        text,
+        "<g stroke=\"{}\" stroke-width=\"{}\">",
+        escape(&style.segment_stroke),
+        style.stroke_width * 2.0
+    )
+    .unwrap();
+    for (v1, v2) in segments.keys() {
+        write_line(&mut text, point(v1), point(v2), "");
+    }
+    writeln!(text, "</g>").unwrap();
+
+    if style.show_vertices {
+        writeln!(text, "<g fill=\"{}\">", escape(&style.vertex_fill)).unwrap();
+        for vertex in solid_vertices.iter() {
+            write_circle(&mut text, point(vertex), style.vertex_radius, "");
+        }
+        writeln!(text, "</g>").unwrap();
+        writeln!(text, "<g fill=\"{}\">", escape(&style.conflict_vertex_fill)).unwrap();
+        let waiting: HashSet<&Rc<Vertex>> = conflict_map.values().collect();
+        for vertex in waiting {
+            write_circle(&mut text, point(vertex), style.vertex_radius * 1.5, "");
+        }
+        writeln!(text, "</g>").unwrap();
+    }
+    writeln!(text, "</svg>").unwrap();
+    text
+}
+
+/* Maps mesh coordinates into the picture, keeping the aspect ratio */
+struct View {
+    scale: f64,
+    x_offset: f64,
+    y_offset: f64,
+    width: f64,
+    height: f64,
+}
+
+impl View {
+    fn new(coordinates: &[f64], style: &SvgStyle) -> Self {
+        let (mut x_min, mut y_min) = (f64::INFINITY, f64::INFINITY);
+        let (mut x_max, mut y_max) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+        for point in coordinates.chunks(2) {
+            x_min = x_min.min(point[0]);
+            x_max = x_max.max(point[0]);
+            y_min = y_min.min(point[1]);
+            y_max = y_max.max(point[1]);
+        }
+        if x_min > x_max {
+            x_min = 0.0;
+            x_max = 0.0;
+            y_min = 0.0;
+            y_max = 0.0;
+        }
+
+        let inner_width = (style.width - 2.0 * style.margin).max(0.0);
+        let inner_height = (style.height - 2.0 * style.margin).max(0.0);
+        let extent = (x_max - x_min).max(y_max - y_min);
+        let scale = if extent > 0.0 {
+            (inner_width / (x_max - x_min)).min(inner_height / (y_max - y_min))
+        } else {
+            1.0
+        };
+        /* centers the drawing in the picture */
+        let x_offset = (style.width - (x_max - x_min) * scale) / 2.0 - x_min * scale;
+        let y_offset = (style.height + (y_max - y_min) * scale) / 2.0 + y_min * scale;
+        Self {
+            scale,
+            x_offset,
+            y_offset,
+            width: style.width,
+            height: style.height,
+        }
+    }
+
+    fn point(&self, x: f64, y: f64) -> (f64, f64) {
+        (
+            self.x_offset + x * self.scale,
+            self.y_offset - y * self.scale,
+        )
+    }
+
+    fn open(&self, text: &mut String, style: &SvgStyle) {
+        writeln!(
+            text,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\" stroke-width=\"{}\">",
+            self.width, self.height, self.width, self.height, style.stroke_width
+        )
+        .unwrap();
+        writeln!(
+            text,
+            "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>",
+            escape(&style.background)
+        )
+        .unwrap();
+    }
+}
+
+/* keeps style strings from closing the attribute they are written into */
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn write_polygon(text: &mut String, corners: &[(f64, f64)], attributes: &str) {
+    let points: Vec<String> = corners
+        .iter()
+        .map(|(x, y)| format!("{:.2},{:.2}", x, y))
+        .collect();
+    writeln!(
+        text,
+        "<polygon points=\"{}\"{}/>",
+        points.join(" "),
+        attributes
+    )
+    .unwrap();
+}
+
+fn write_line(text: &mut String, from: (f64, f64), to: (f64, f64), attributes: &str) {
+    writeln!(
+        text,
+        "<line x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\"{}/>",
+        from.0, from.1, to.0, to.1, attributes
+    )
+    .unwrap();
+}
+
+fn write_circle(text: &mut String, center: (f64, f64), radius: f64, attributes: &str) {
+    writeln!(
+        text,
+        "<circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"{:.2}\"{}/>",
+        center.0, center.1, radius, attributes
+    )
+    .unwrap();
+}
+
+#[cfg(test)]
+mod write_svg {
+    use super::*;
+
+    #[test]
+    fn test_fits_picture_with_y_up() {
+        let triangulation = Triangulation::from(vec![0.0, 0.0, 2.0, 0.0, 0.0, 1.0], vec![0, 1, 2]);
+        let style = SvgStyle {
+            width: 220.0,
+            height: 120.0,
+            margin: 10.0,
+            ..SvgStyle::default()
+        };
+        let text = write_svg(&triangulation, &style);
+        assert!(text.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"220\""));
+        assert!(text.contains("<polygon points=\"10.00,110.00 210.00,110.00 10.00,10.00\"/>"));
+        assert_eq!(text.matches("<circle").count(), 3);
+        assert!(text.trim_end().ends_with("</svg>"));
+    }
+
+    #[test]
+    fn test_escapes_style() {
+        let triangulation = Triangulation::from(vec![0.0, 0.0, 2.0, 0.0, 0.0, 1.0], vec![0, 1, 2]);
+        let style = SvgStyle {
+            triangle_fill: "url(#a)\" onload=\"x()".to_string(),
+            ..SvgStyle::default()
+        };
+        let text = write_svg(&triangulation, &style);
+        assert!(text.contains("fill=\"url(#a)&quot; onload=&quot;x()\""));
+    }
+}
@@ -59,6 +59,175 @@ impl Triangle {
         return matrix.determinant() / 2.0;
     }
 
+    /**
+     * Center of the circumcircle, which is a vertex of the Voronoi diagram.
+     * Ghost triangles have their circumcenter at infinity.
+     */
+    pub fn circumcenter(&self) -> Vertex {
+        if self.is_ghost() {
+            panic!("Ghost triangles have no circumcenter");
+        }
+
+        let (a, b, c) = (&self.v1, &self.v2, &self.v3);
+        let a_norm = a.x.powi(2) + a.y.powi(2);
+        let b_norm = b.x.powi(2) + b.y.powi(2);
+        let c_norm = c.x.powi(2) + c.y.powi(2);
+        let divisor = 2.0 * (a.x * (b.y - c.y) + b.x * (c.y - a.y) + c.x * (a.y - b.y));
+
+        Vertex::new(
+            (a_norm * (b.y - c.y) + b_norm * (c.y - a.y) + c_norm * (a.y - b.y)) / divisor,
+            (a_norm * (c.x - b.x) + b_norm * (a.x - c.x) + c_norm * (b.x - a.x)) / divisor,
+        )
+    }
+
+    /**
+     * Upward unit normal of the plane through the vertices with their elevations.
+     * None for ghost triangles or if a vertex has no elevation.
+     */
+    pub fn normal(&self) -> Option<(f64, f64, f64)> {
+        let (ux, uy, uz, vx, vy, vz) = self.edge_vectors()?;
+        let (nx, ny, nz) = (uy * vz - uz * vy, uz * vx - ux * vz, ux * vy - uy * vx);
+        let norm = (nx.powi(2) + ny.powi(2) + nz.powi(2)).sqrt();
+        Some((nx / norm, ny / norm, nz / norm))
+    }
+
+    /**
+     * Angle between the plane and the horizontal, in degrees.
+     */
+    pub fn slope(&self) -> Option<f64> {
+        let (nx, ny, nz) = self.normal()?;
+        Some(nx.hypot(ny).atan2(nz).to_degrees())
+    }
+
+    /**
+     * Compass direction the plane faces downhill, in degrees clockwise from north,
+     * the y axis. Flat triangles have no aspect.
+     */
+    pub fn aspect(&self) -> Option<f64> {
+        let (nx, ny, _) = self.normal()?;
+        if nx == 0.0 && ny == 0.0 {
+            return None;
+        }
+        Some(nx.atan2(ny).to_degrees().rem_euclid(360.0))
+    }
+
+    /**
+     * Area of the triangle in space, lifted by the elevations.
+     */
+    pub fn surface_area(&self) -> Option<f64> {
+        let (ux, uy, uz, vx, vy, vz) = self.edge_vectors()?;
+        let (nx, ny, nz) = (uy * vz - uz * vy, uz * vx - ux * vz, ux * vy - uy * vx);
+        Some((nx.powi(2) + ny.powi(2) + nz.powi(2)).sqrt() / 2.0)
+    }
+
+    /**
+     * Volume between the plane and the datum, where the plane lies above it.
+     * The triangle is cut along its contour at the datum, and the part above
+     * is split in a fan of triangles, each a prism truncated by the plane.
+     */
+    pub fn volume_above(&self, datum: f64) -> Option<f64> {
+        if self.is_ghost() {
+            return None;
+        }
+
+        let corners = [
+            (self.v1.x, self.v1.y, self.v1.z? - datum),
+            (self.v2.x, self.v2.y, self.v2.z? - datum),
+            (self.v3.x, self.v3.y, self.v3.z? - datum),
+        ];
+        let mut above: Vec<(f64, f64, f64)> = Vec::new();
+        for index in 0..3 {
+            let (x1, y1, h1) = corners[index];
+            let (x2, y2, h2) = corners[(index + 1) % 3];
+            if h1 >= 0.0 {
+                above.push((x1, y1, h1));
+            }
+            if (h1 >= 0.0) != (h2 >= 0.0) {
+                let ratio = h1 / (h1 - h2);
+                above.push((x1 + (x2 - x1) * ratio, y1 + (y2 - y1) * ratio, 0.0));
+            }
+        }
+
+        let mut volume = 0.0;
+        for index in 1..above.len().saturating_sub(1) {
+            let (x1, y1, h1) = above[0];
+            let (x2, y2, h2) = above[index];
+            let (x3, y3, h3) = above[index + 1];
+            let area = ((x2 - x1) * (y3 - y1) - (x3 - x1) * (y2 - y1)) / 2.0;
+            volume += area * (h1 + h2 + h3) / 3.0;
+        }
+        Some(volume)
+    }
+
+    fn edge_vectors(&self) -> Option<(f64, f64, f64, f64, f64, f64)> {
+        if self.is_ghost() {
+            return None;
+        }
+        let (z1, z2, z3) = (self.v1.z?, self.v2.z?, self.v3.z?);
+        Some((
+            self.v2.x - self.v1.x,
+            self.v2.y - self.v1.y,
+            z2 - z1,
+            self.v3.x - self.v1.x,
+            self.v3.y - self.v1.y,
+            z3 - z1,
+        ))
+    }
+
+    pub fn circumradius(&self) -> f64 {
+        let center = self.circumcenter();
+        (self.v1.x - center.x).hypot(self.v1.y - center.y)
+    }
+
+    /**
+     * Directed edges, following the counterclockwise order of the vertices.
+     */
+    pub fn edges(&self) -> Vec<(Rc<Vertex>, Rc<Vertex>)> {
+        vec![
+            (Rc::clone(&self.v1), Rc::clone(&self.v2)),
+            (Rc::clone(&self.v2), Rc::clone(&self.v3)),
+            (Rc::clone(&self.v3), Rc::clone(&self.v1)),
+        ]
+    }
+
+    /**
+     * Checks whether the vertex lies inside the triangle itself, not its circumcircle.
+     * Ghost triangles contain nothing.
+     */
+    pub fn contains(&self, vertex: &Vertex) -> Continence {
+        if self.is_ghost() {
+            return Continence::Outside;
+        }
+
+        let orientations = [
+            orient_2d(&self.v1, &self.v2, vertex),
+            orient_2d(&self.v2, &self.v3, vertex),
+            orient_2d(&self.v3, &self.v1, vertex),
+        ];
+
+        if orientations.contains(&Orientation::Clockwise) {
+            Continence::Outside
+        } else if orientations.contains(&Orientation::Colinear) {
+            Continence::Boundary
+        } else {
+            Continence::Inside
+        }
+    }
+
+    /**
+     * Checks whether the vertex lies strictly between the solid vertices of a ghost triangle.
+     */
+    fn solid_edge_contains(&self, vertex: &Vertex) -> bool {
+        let (v1, v2) = if self.v1.is_ghost {
+            (&self.v2, &self.v3)
+        } else if self.v2.is_ghost {
+            (&self.v3, &self.v1)
+        } else {
+            (&self.v1, &self.v2)
+        };
+        (vertex.x - v1.x) * (vertex.x - v2.x) + (vertex.y - v1.y) * (vertex.y - v2.y) < 0.0
+    }
+
     pub fn encircles(&self, vertex: &Vertex) -> Continence {
         if !self.is_ghost() {
             /*
@@ -70,9 +239,14 @@ impl Triangle {
                The set of ghost triangles surround the convex hull with solid edges
                in counterclockwise direction. The first two vertices have the outer
                space in counterclockwise direction, as the ghost is always outside.
+               A vertex lying on the open solid edge is also in conflict, otherwise
+               it would be inserted as a flat triangle.
             */
             match orient_2d(&self.v1, &self.v2, &vertex) {
                 Orientation::Counterclockwise => return Continence::Inside,
+                Orientation::Colinear if self.solid_edge_contains(vertex) => {
+                    return Continence::Inside
+                }
                 _ => return Continence::Outside,
             }
         }
@@ -163,6 +337,19 @@ mod encircles {
         let v4 = Rc::new(Vertex::new(1.0, 1.0));
         assert_eq!(t1.encircles(&v4), Continence::Boundary);
     }
+
+    #[test]
+    fn test_ghost_triangle_encircles_open_edge() {
+        let v1 = Rc::new(Vertex::new(1.0, 0.0));
+        let v2 = Rc::new(Vertex::new(0.0, 0.0));
+        let ghost = Rc::new(Vertex::new_ghost());
+        let t1 = Triangle::new(&v1, &v2, &ghost);
+
+        assert_eq!(t1.encircles(&Vertex::new(0.5, -1.0)), Continence::Inside);
+        assert_eq!(t1.encircles(&Vertex::new(0.5, 1.0)), Continence::Outside);
+        assert_eq!(t1.encircles(&Vertex::new(0.5, 0.0)), Continence::Inside);
+        assert_eq!(t1.encircles(&Vertex::new(2.0, 0.0)), Continence::Outside);
+    }
 }
 
 #[cfg(test)]
@@ -187,3 +374,125 @@ mod area {
         assert_eq!(t1.area(), 0.0);
     }
 }
+
+#[cfg(test)]
+mod circumcenter {
+    use super::*;
+
+    #[test]
+    fn test_circumcenter() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(2.0, 0.0));
+        let v3 = Rc::new(Vertex::new(0.0, 2.0));
+        let t1 = Triangle::new(&v1, &v2, &v3);
+        assert!(t1.circumcenter() == Vertex::new(1.0, 1.0));
+    }
+
+    #[test]
+    fn test_circumradius() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(6.0, 0.0));
+        let v3 = Rc::new(Vertex::new(0.0, 8.0));
+        assert_eq!(Triangle::new(&v1, &v2, &v3).circumradius(), 5.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_ghost_triangle_has_no_circumcenter() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(1.0, 0.0));
+        let v3 = Rc::new(Vertex::new_ghost());
+        Triangle::new(&v1, &v2, &v3).circumcenter();
+    }
+}
+
+#[cfg(test)]
+mod contains {
+    use super::*;
+
+    #[test]
+    fn test_triangle_contains_method() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(1.0, 0.0));
+        let v3 = Rc::new(Vertex::new(0.0, 1.0));
+        let t1 = Triangle::new(&v1, &v2, &v3);
+
+        let v4 = Vertex::new(0.2, 0.2);
+        assert_eq!(t1.contains(&v4), Continence::Inside);
+
+        let v4 = Vertex::new(0.8, 0.8);
+        assert_eq!(t1.contains(&v4), Continence::Outside);
+
+        let v4 = Vertex::new(0.5, 0.0);
+        assert_eq!(t1.contains(&v4), Continence::Boundary);
+    }
+
+    #[test]
+    fn test_ghost_triangle_contains_nothing() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(1.0, 0.0));
+        let v3 = Rc::new(Vertex::new_ghost());
+        let t1 = Triangle::new(&v1, &v2, &v3);
+        assert_eq!(t1.contains(&Vertex::new(0.5, -1.0)), Continence::Outside);
+    }
+}
+
+#[cfg(test)]
+mod terrain {
+    use super::*;
+
+    fn inclined() -> Triangle {
+        /* rises by 1 per unit towards east, so it faces west */
+        let v1 = Rc::new(Vertex::new_with_z(0.0, 0.0, 0.0));
+        let v2 = Rc::new(Vertex::new_with_z(1.0, 0.0, 1.0));
+        let v3 = Rc::new(Vertex::new_with_z(0.0, 1.0, 0.0));
+        Triangle::new(&v1, &v2, &v3)
+    }
+
+    #[test]
+    fn test_normal_slope_and_aspect() {
+        let triangle = inclined();
+        let (nx, ny, nz) = triangle.normal().unwrap();
+        let half_sqrt = 0.5_f64.sqrt();
+        assert!((nx + half_sqrt).abs() < 1e-12 && ny == 0.0 && (nz - half_sqrt).abs() < 1e-12);
+        assert!((triangle.slope().unwrap() - 45.0).abs() < 1e-12);
+        assert_eq!(triangle.aspect(), Some(270.0));
+    }
+
+    #[test]
+    fn test_flat_triangle_has_no_aspect() {
+        let v1 = Rc::new(Vertex::new_with_z(0.0, 0.0, 2.0));
+        let v2 = Rc::new(Vertex::new_with_z(1.0, 0.0, 2.0));
+        let v3 = Rc::new(Vertex::new_with_z(0.0, 1.0, 2.0));
+        let triangle = Triangle::new(&v1, &v2, &v3);
+        assert_eq!(triangle.slope(), Some(0.0));
+        assert_eq!(triangle.aspect(), None);
+    }
+
+    #[test]
+    fn test_surface_area() {
+        let area = inclined().surface_area().unwrap();
+        assert!((area - 0.5 * 2.0_f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_volume_above_datum() {
+        let triangle = inclined();
+        assert!((triangle.volume_above(0.0).unwrap() - 1.0 / 6.0).abs() < 1e-12);
+        assert!((triangle.volume_above(-1.0).unwrap() - (0.5 + 1.0 / 6.0)).abs() < 1e-12);
+        assert_eq!(triangle.volume_above(1.0), Some(0.0));
+
+        /* only the corner above 0.5 remains, a pyramid of base 1/8 and height 1/2 */
+        assert!((triangle.volume_above(0.5).unwrap() - 1.0 / 48.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_no_elevation() {
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(1.0, 0.0));
+        let v3 = Rc::new(Vertex::new(0.0, 1.0));
+        let triangle = Triangle::new(&v1, &v2, &v3);
+        assert_eq!(triangle.normal(), None);
+        assert_eq!(triangle.volume_above(0.0), None);
+    }
+}
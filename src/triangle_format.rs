@@ -0,0 +1,646 @@
+use crate::parse_error::*;
+use crate::triangulation::*;
+use crate::triangulator::*;
+use crate::vertex::*;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::rc::Rc;
+
+/* Files of Shewchuk's Triangle.
+
+    - .node: vertices, with attributes and boundary markers.
+    - .poly: a planar straight line graph, with vertices (or none, if
+    they are in a .node file), segments, hole points and regions.
+    - .ele: triangles, with their regional attribute.
+    - .edge: edges, with boundary markers.
+    - .neigh: the neighbors of each triangle, -1 for none.
+
+Lines hold whitespace separated numbers, and '#' starts a comment. Each
+item starts with its number, counting from 0 or 1 as the first vertex
+of the .node or .poly file does. Files are written counting from 1, as
+Triangle does by default.  */
+
+/* Planar straight line graph, as read from .node and .poly files */
+pub struct Pslg {
+    pub coordinates: Vec<f64>,
+    pub vertex_markers: Vec<i32>,
+    /* pairs of vertex indices, counting from 0 */
+    pub segments: Vec<usize>,
+    pub segment_markers: Vec<i32>,
+    /* pairs of x,y coordinates */
+    pub holes: Vec<f64>,
+    /* x, y, attribute and maximum area of each region, the area not positive if unconstrained */
+    pub regions: Vec<f64>,
+}
+
+impl Pslg {
+    /**
+     * Triangulates the vertices, then inserts segments, carves holes and
+     * tags regions, refining those with an area constraint.
+     */
+    pub fn triangulate(&self) -> Triangulator {
+        let vertices = Vertex::from_coordinates(self.coordinates.clone());
+        let mut triangulator = Triangulator::from_vertices(vertices.clone());
+        triangulator.triangulate();
+
+        for (vertex, marker) in vertices.iter().zip(self.vertex_markers.iter()) {
+            if *marker != 0 {
+                triangulator.set_vertex_marker(Rc::clone(vertex), *marker);
+            }
+        }
+        for (segment, marker) in self.segments.chunks(2).zip(self.segment_markers.iter()) {
+            triangulator.insert_marked_segment(
+                Rc::clone(&vertices[segment[0]]),
+                Rc::clone(&vertices[segment[1]]),
+                *marker,
+            );
+        }
+        for hole in self.holes.chunks(2) {
+            triangulator.insert_hole_seed(Rc::new(Vertex::new(hole[0], hole[1])));
+        }
+        for region in self.regions.chunks(4) {
+            let max_area = if region[3] > 0.0 {
+                Some(region[3])
+            } else {
+                None
+            };
+            triangulator.insert_region(
+                Rc::new(Vertex::new(region[0], region[1])),
+                region[2],
+                max_area,
+            );
+        }
+        triangulator
+    }
+}
+
+/* Lines holding data, and the numbering base of items */
+struct Reader<'a> {
+    lines: LineReader<'a>,
+    first_index: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(text: &'a str) -> Self {
+        let lines = text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| {
+                let content = line.split('#').next().unwrap_or("");
+                (index + 1, content.split_whitespace().collect::<Vec<&str>>())
+            })
+            .filter(|(_, tokens)| !tokens.is_empty())
+            .collect();
+        Self {
+            lines: LineReader::new(lines),
+            first_index: 0,
+        }
+    }
+
+    /* boundary markers are integers of any sign, 0 standing for none */
+    fn marker(line: usize, number: f64) -> Result<i32, ParseError> {
+        if number.fract() != 0.0 || number.abs() > i32::MAX as f64 {
+            return Err(ParseError::new(
+                line,
+                &format!("expected a marker, found {}", number),
+            ));
+        }
+        Ok(number as i32)
+    }
+
+    /**
+     * Converts an item number of the file into an index counting from 0.
+     */
+    fn index(&self, line: usize, number: f64, size: usize) -> Result<usize, ParseError> {
+        let index = LineReader::count(line, number)?;
+        if index < self.first_index || index - self.first_index >= size {
+            return Err(ParseError::new(
+                line,
+                &format!("index {} out of range", index),
+            ));
+        }
+        Ok(index - self.first_index)
+    }
+
+    /**
+     * Reads the vertices section of .node and .poly files.
+     */
+    fn vertices(&mut self, pslg: &mut Pslg) -> Result<(), ParseError> {
+        let (line, header) = self.lines.numbers(1, "vertices header")?;
+        let size = LineReader::count(line, header[0])?;
+        if header.len() > 1 && header[1] != 2.0 {
+            return Err(ParseError::new(line, "only two dimensions are supported"));
+        }
+        let attributes_size = match header.get(2) {
+            Some(number) => LineReader::count(line, *number)?,
+            None => 0,
+        };
+        let has_markers = header.get(3).is_some_and(|number| *number != 0.0);
+
+        let marker_position = 3 + attributes_size;
+        let expected_size = if has_markers {
+            marker_position + 1
+        } else {
+            marker_position
+        };
+        for position in 0..size {
+            let (line, numbers) = self.lines.numbers(expected_size, "vertex")?;
+            if position == 0 {
+                self.first_index = LineReader::count(line, numbers[0])?.min(1);
+            }
+            pslg.coordinates.push(numbers[1]);
+            pslg.coordinates.push(numbers[2]);
+            pslg.vertex_markers.push(if has_markers {
+                Self::marker(line, numbers[marker_position])?
+            } else {
+                0
+            });
+        }
+        Ok(())
+    }
+}
+
+pub fn read_node(text: &str) -> Result<Pslg, ParseError> {
+    let mut reader = Reader::new(text);
+    let mut pslg = empty_pslg();
+    reader.vertices(&mut pslg)?;
+    Ok(pslg)
+}
+
+/**
+ * Reads a .poly file. Its vertices may be left to a .node file, given apart.
+ */
+pub fn read_poly(text: &str, node: Option<&str>) -> Result<Pslg, ParseError> {
+    let mut reader = Reader::new(text);
+    let mut pslg = empty_pslg();
+    reader.vertices(&mut pslg)?;
+    if pslg.coordinates.is_empty() {
+        if let Some(node) = node {
+            let mut node_reader = Reader::new(node);
+            node_reader.vertices(&mut pslg)?;
+            reader.first_index = node_reader.first_index;
+        }
+    }
+    let vertices_size = pslg.coordinates.len() / 2;
+
+    let (line, header) = reader.lines.numbers(1, "segments header")?;
+    let segments_size = LineReader::count(line, header[0])?;
+    let has_markers = header.get(1).is_some_and(|number| *number != 0.0);
+    for _ in 0..segments_size {
+        let (line, numbers) = reader
+            .lines
+            .numbers(if has_markers { 4 } else { 3 }, "segment")?;
+        pslg.segments
+            .push(reader.index(line, numbers[1], vertices_size)?);
+        pslg.segments
+            .push(reader.index(line, numbers[2], vertices_size)?);
+        pslg.segment_markers.push(if has_markers {
+            Reader::marker(line, numbers[3])?
+        } else {
+            0
+        });
+    }
+
+    let (line, header) = reader.lines.numbers(1, "holes header")?;
+    for _ in 0..LineReader::count(line, header[0])? {
+        let (_, numbers) = reader.lines.numbers(3, "hole")?;
+        pslg.holes.push(numbers[1]);
+        pslg.holes.push(numbers[2]);
+    }
+
+    if reader.lines.has_next_line() {
+        let (line, header) = reader.lines.numbers(1, "regions header")?;
+        for _ in 0..LineReader::count(line, header[0])? {
+            let (_, numbers) = reader.lines.numbers(4, "region")?;
+            pslg.regions.push(numbers[1]);
+            pslg.regions.push(numbers[2]);
+            pslg.regions.push(numbers[3]);
+            pslg.regions.push(*numbers.get(4).unwrap_or(&-1.0));
+        }
+    }
+    Ok(pslg)
+}
+
+/**
+ * Reads a mesh from its .node and .ele files, with edges and neighbors
+ * from the .edge and .neigh files if given.
+ */
+pub fn read_mesh(
+    node: &str,
+    ele: &str,
+    edge: Option<&str>,
+    neigh: Option<&str>,
+) -> Result<Triangulation, ParseError> {
+    let mut node_reader = Reader::new(node);
+    let mut pslg = empty_pslg();
+    node_reader.vertices(&mut pslg)?;
+    let vertices_size = pslg.coordinates.len() / 2;
+
+    let mut reader = Reader::new(ele);
+    reader.first_index = node_reader.first_index;
+    let (line, header) = reader.lines.numbers(1, "triangles header")?;
+    let triangles_size = LineReader::count(line, header[0])?;
+    let corners = match header.get(1) {
+        Some(number) => LineReader::count(line, *number)?,
+        None => 3,
+    };
+    if corners != 3 {
+        return Err(ParseError::new(
+            line,
+            "only triangles with 3 nodes are supported",
+        ));
+    }
+    let has_attribute = header.get(2).is_some_and(|number| *number != 0.0);
+
+    let mut triangles: Vec<usize> = Vec::new();
+    let mut attributes: Vec<f64> = Vec::new();
+    for _ in 0..triangles_size {
+        let (line, numbers) = reader
+            .lines
+            .numbers(if has_attribute { 5 } else { 4 }, "triangle")?;
+        for number in &numbers[1..4] {
+            triangles.push(reader.index(line, *number, vertices_size)?);
+        }
+        attributes.push(if has_attribute { numbers[4] } else { 0.0 });
+    }
+
+    let mut triangulation = Triangulation::from(pslg.coordinates, triangles);
+    triangulation.attributes = attributes;
+    triangulation.vertex_markers = pslg.vertex_markers;
+
+    if let Some(edge) = edge {
+        let mut reader = Reader::new(edge);
+        reader.first_index = node_reader.first_index;
+        let (line, header) = reader.lines.numbers(1, "edges header")?;
+        let has_markers = header.get(1).is_some_and(|number| *number != 0.0);
+        for _ in 0..LineReader::count(line, header[0])? {
+            let (line, numbers) = reader
+                .lines
+                .numbers(if has_markers { 4 } else { 3 }, "edge")?;
+            let v1 = reader.index(line, numbers[1], vertices_size)?;
+            let v2 = reader.index(line, numbers[2], vertices_size)?;
+            triangulation.edges.push(v1);
+            triangulation.edges.push(v2);
+            let marker = if has_markers {
+                Reader::marker(line, numbers[3])?
+            } else {
+                0
+            };
+            if marker != 0 {
+                triangulation.boundary_edges.push(v1);
+                triangulation.boundary_edges.push(v2);
+                triangulation.boundary_markers.push(marker);
+            }
+        }
+    }
+
+    if let Some(neigh) = neigh {
+        let mut reader = Reader::new(neigh);
+        reader.first_index = node_reader.first_index;
+        let (line, header) = reader.lines.numbers(1, "neighbors header")?;
+        if LineReader::count(line, header[0])? != triangles_size {
+            return Err(ParseError::new(
+                line,
+                "neighbors do not match the triangles",
+            ));
+        }
+        triangulation.neighbors = vec![NO_NEIGHBOR; triangles_size * 3];
+        for triangle in 0..triangles_size {
+            let (line, numbers) = reader.lines.numbers(4, "neighbors")?;
+            /* Triangle lists the neighbor opposite each corner */
+            for corner in 0..3 {
+                if numbers[corner + 1] >= 0.0 {
+                    triangulation.neighbors[triangle * 3 + (corner + 1) % 3] =
+                        reader.index(line, numbers[corner + 1], triangles_size)?;
+                }
+            }
+        }
+    }
+    Ok(triangulation)
+}
+
+pub fn write_node(triangulation: &Triangulation) -> String {
+    let mut text = String::new();
+    let vertices_size = triangulation.coordinates.len() / 2;
+    writeln!(text, "{} 2 0 1", vertices_size).unwrap();
+    for index in 0..vertices_size {
+        writeln!(
+            text,
+            "{} {} {} {}",
+            index + 1,
+            triangulation.coordinates[index * 2],
+            triangulation.coordinates[index * 2 + 1],
+            triangulation.vertex_markers.get(index).unwrap_or(&0)
+        )
+        .unwrap();
+    }
+    text
+}
+
+/**
+ * Writes the vertices and the boundary edges as segments. Holes and regions are
+ * not kept by the triangulation, so none are written.
+ */
+pub fn write_poly(triangulation: &Triangulation) -> String {
+    let mut text = write_node(triangulation);
+    let segments_size = triangulation.boundary_edges.len() / 2;
+    writeln!(text, "{} 1", segments_size).unwrap();
+    for index in 0..segments_size {
+        writeln!(
+            text,
+            "{} {} {} {}",
+            index + 1,
+            triangulation.boundary_edges[index * 2] + 1,
+            triangulation.boundary_edges[index * 2 + 1] + 1,
+            triangulation.boundary_markers.get(index).unwrap_or(&0)
+        )
+        .unwrap();
+    }
+    writeln!(text, "0").unwrap();
+    text
+}
+
+pub fn write_ele(triangulation: &Triangulation) -> String {
+    let mut text = String::new();
+    let triangles_size = triangulation.triangles.len() / 3;
+    let has_attribute = triangulation
+        .attributes
+        .iter()
+        .any(|attribute| *attribute != 0.0);
+    writeln!(
+        text,
+        "{} 3 {}",
+        triangles_size,
+        if has_attribute { 1 } else { 0 }
+    )
+    .unwrap();
+    for index in 0..triangles_size {
+        write!(
+            text,
+            "{} {} {} {}",
+            index + 1,
+            triangulation.triangles[index * 3] + 1,
+            triangulation.triangles[index * 3 + 1] + 1,
+            triangulation.triangles[index * 3 + 2] + 1
+        )
+        .unwrap();
+        if has_attribute {
+            write!(text, " {}", triangulation.attributes[index]).unwrap();
+        }
+        writeln!(text).unwrap();
+    }
+    text
+}
+
+/**
+ * Writes the edges with the markers of the boundary edges. As Triangle does,
+ * unmarked boundary edges are written with 1 and interior ones with 0.
+ */
+pub fn write_edge(triangulation: &Triangulation) -> String {
+    let mut markers: HashMap<(usize, usize), i32> = HashMap::new();
+    for (edge, marker) in triangulation
+        .boundary_edges
+        .chunks(2)
+        .zip(triangulation.boundary_markers.iter())
+    {
+        let marker = if *marker == 0 { 1 } else { *marker };
+        markers.insert((edge[0].min(edge[1]), edge[0].max(edge[1])), marker);
+    }
+
+    let mut text = String::new();
+    let edges_size = triangulation.edges.len() / 2;
+    writeln!(text, "{} 1", edges_size).unwrap();
+    for index in 0..edges_size {
+        let (v1, v2) = (
+            triangulation.edges[index * 2],
+            triangulation.edges[index * 2 + 1],
+        );
+        let marker = markers.get(&(v1.min(v2), v1.max(v2))).unwrap_or(&0);
+        writeln!(text, "{} {} {} {}", index + 1, v1 + 1, v2 + 1, marker).unwrap();
+    }
+    text
+}
+
+pub fn write_neigh(triangulation: &Triangulation) -> String {
+    let mut text = String::new();
+    let triangles_size = triangulation.neighbors.len() / 3;
+    writeln!(text, "{} 3", triangles_size).unwrap();
+    for index in 0..triangles_size {
+        write!(text, "{}", index + 1).unwrap();
+        /* the neighbor opposite each corner lies across the edge of the next corners */
+        for corner in 0..3 {
+            match triangulation.neighbors[index * 3 + (corner + 1) % 3] {
+                NO_NEIGHBOR => write!(text, " -1").unwrap(),
+                neighbor => write!(text, " {}", neighbor + 1).unwrap(),
+            }
+        }
+        writeln!(text).unwrap();
+    }
+    text
+}
+
+fn empty_pslg() -> Pslg {
+    Pslg {
+        coordinates: Vec::new(),
+        vertex_markers: Vec::new(),
+        segments: Vec::new(),
+        segment_markers: Vec::new(),
+        holes: Vec::new(),
+        regions: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod read_poly {
+    use super::*;
+
+    const SQUARE_WITH_HOLE: &str = "
+        # outer and inner squares
+        8 2 0 1
+        1 0 0 1
+        2 4 0 1
+        3 4 4 1
+        4 0 4 1
+        5 1 1 2
+        6 3 1 2
+        7 3 3 2
+        8 1 3 2
+        8 1
+        1 1 2 1
+        2 2 3 1
+        3 3 4 1
+        4 4 1 1
+        5 5 6 2
+        6 6 7 2
+        7 7 8 2
+        8 8 5 2
+        1
+        1 2 2
+        1
+        1 0.5 0.5 7 -1
+    ";
+
+    #[test]
+    fn test_reads_pslg() {
+        let pslg = read_poly(SQUARE_WITH_HOLE, None).unwrap();
+        assert_eq!(pslg.coordinates.len(), 16);
+        assert_eq!(pslg.vertex_markers, vec![1, 1, 1, 1, 2, 2, 2, 2]);
+        assert_eq!(pslg.segments[..4], [0, 1, 1, 2]);
+        assert_eq!(pslg.segment_markers[7], 2);
+        assert_eq!(pslg.holes, vec![2.0, 2.0]);
+        assert_eq!(pslg.regions, vec![0.5, 0.5, 7.0, -1.0]);
+    }
+
+    #[test]
+    fn test_triangulates_pslg() {
+        let triangulation = read_poly(SQUARE_WITH_HOLE, None)
+            .unwrap()
+            .triangulate()
+            .export_preserving_order();
+        assert_eq!(triangulation.triangles.len() / 3, 8);
+        assert!(triangulation
+            .attributes
+            .iter()
+            .all(|attribute| *attribute == 7.0));
+        assert_eq!(
+            triangulation
+                .boundary_markers
+                .iter()
+                .filter(|marker| **marker == 2)
+                .count(),
+            4
+        );
+    }
+
+    #[test]
+    fn test_triangulates_hole_next_to_constrained_region() {
+        let triangulator = read_poly(
+            "
+            8 2 0 0
+            1 0 0
+            2 1 0
+            3 1 1
+            4 0 1
+            5 0.4 0.4
+            6 0.6 0.4
+            7 0.6 0.6
+            8 0.4 0.6
+            8 0
+            1 1 2
+            2 2 3
+            3 3 4
+            4 4 1
+            5 5 6
+            6 6 7
+            7 7 8
+            8 8 5
+            1
+            1 0.5 0.5
+            1
+            1 0.1 0.1 3 0.03
+            ",
+            None,
+        )
+        .unwrap()
+        .triangulate();
+        let triangulation = triangulator.export_preserving_order();
+
+        let mut area = 0.0;
+        for (index, triangle) in triangulation.triangles.chunks(3).enumerate() {
+            let corner = |k: usize| {
+                (
+                    triangulation.coordinates[triangle[k] * 2],
+                    triangulation.coordinates[triangle[k] * 2 + 1],
+                )
+            };
+            let ((x1, y1), (x2, y2), (x3, y3)) = (corner(0), corner(1), corner(2));
+            let triangle_area = ((x2 - x1) * (y3 - y1) - (y2 - y1) * (x3 - x1)) / 2.0;
+            assert!(triangle_area > 0.0 && triangle_area <= 0.03);
+            assert_eq!(triangulation.attributes[index], 3.0);
+            area += triangle_area;
+        }
+        assert!((area - 0.96).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_reads_negative_markers() {
+        let poly = "3 2 0 1\n1 0 0 -2\n2 1 0 0\n3 0 1 5\n1 1\n1 1 2 -3\n0\n";
+        let pslg = read_poly(poly, None).unwrap();
+        assert_eq!(pslg.vertex_markers, vec![-2, 0, 5]);
+        assert_eq!(pslg.segment_markers, vec![-3]);
+
+        let error = read_poly("1 2 0 1\n1 0 0 0.5\n0 0\n0\n", None)
+            .err()
+            .unwrap();
+        assert_eq!(error.to_string(), "line 2: expected a marker, found 0.5");
+    }
+
+    #[test]
+    fn test_vertices_from_node_file() {
+        let node = "3 2 0 0\n0 0 0\n1 1 0\n2 0 1\n";
+        let poly = "0 2 0 0\n1 0\n0 1 2\n0\n";
+        let pslg = read_poly(poly, Some(node)).unwrap();
+        assert_eq!(pslg.coordinates.len(), 6);
+        assert_eq!(pslg.segments, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_reports_line_of_errors() {
+        let node = "2 2 0 0\n1 0 0\n2 1 x\n";
+        assert_eq!(read_node(node).err().unwrap().line, 3);
+
+        let poly = "2 2 0 0\n1 0 0\n2 1 0\n1 0\n1 1 3\n0\n";
+        let error = read_poly(poly, None).err().unwrap();
+        assert_eq!(error.to_string(), "line 5: index 3 out of range");
+
+        let error = read_poly("1 2 0 0\n1 0 0\n", None).err().unwrap();
+        assert_eq!(error.line, 0);
+    }
+}
+
+#[cfg(test)]
+mod round_trip {
+    use super::*;
+
+    #[test]
+    fn test_mesh_round_trip() {
+        let mut triangulator =
+            Triangulator::from_coordinates(vec![0.0, 0.0, 2.0, 0.0, 2.0, 2.0, 0.0, 2.0, 0.7, 1.2]);
+        triangulator.triangulate();
+        triangulator.insert_marked_segment(
+            Rc::new(Vertex::new(0.0, 0.0)),
+            Rc::new(Vertex::new(2.0, 0.0)),
+            3,
+        );
+        let triangulation = triangulator.export_preserving_order();
+
+        let read = read_mesh(
+            &write_node(&triangulation),
+            &write_ele(&triangulation),
+            Some(&write_edge(&triangulation)),
+            Some(&write_neigh(&triangulation)),
+        )
+        .unwrap();
+        assert_eq!(read.coordinates, triangulation.coordinates);
+        assert_eq!(read.triangles, triangulation.triangles);
+        assert_eq!(read.vertex_markers, triangulation.vertex_markers);
+        assert_eq!(read.edges, triangulation.edges);
+        assert_eq!(read.neighbors, triangulation.neighbors);
+        /* the other hull edges come back with Triangle's default marker */
+        let mut markers = read.boundary_markers.clone();
+        markers.sort();
+        assert_eq!(markers, vec![1, 1, 1, 3]);
+    }
+
+    #[test]
+    fn test_poly_round_trip() {
+        let mut triangulator = Triangulator::from_coordinates(vec![0.0, 0.0, 2.0, 0.0, 1.0, 2.0]);
+        triangulator.triangulate();
+        let triangulation = triangulator.export_preserving_order();
+
+        let pslg = read_poly(&write_poly(&triangulation), None).unwrap();
+        assert_eq!(pslg.coordinates, triangulation.coordinates);
+        assert_eq!(pslg.segments.len(), 6);
+        assert!(pslg.holes.is_empty());
+    }
+}
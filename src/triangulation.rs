@@ -1,16 +1,168 @@
+use crate::contour::*;
+use crate::parse_error::*;
+use crate::triangle::*;
+use crate::vertex::*;
 use std::fmt;
+use std::rc::Rc;
+use std::str::FromStr;
 
+/* Sentinel for the neighbor of a triangle across a hull or hole edge */
+pub const NO_NEIGHBOR: usize = usize::MAX;
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Triangulation {
     pub coordinates: Vec<f64>,
     pub triangles: Vec<usize>,
+    /* regional attribute of each triangle, parallel to the triangles chunks */
+    pub attributes: Vec<f64>,
+    /* boundary marker of each vertex, 0 for unmarked vertices */
+    pub vertex_markers: Vec<i32>,
+    /* pairs of vertex indices of constrained and hull edges */
+    pub boundary_edges: Vec<usize>,
+    /* boundary marker of each boundary edge, 0 for unmarked edges */
+    pub boundary_markers: Vec<i32>,
+    /* three per triangle, the i-th across the edge from the i-th to the next vertex */
+    pub neighbors: Vec<usize>,
+    /* pairs of vertex indices, each edge listed once */
+    pub edges: Vec<usize>,
+    /* pairs of triangles of each edge, the first one holding the edge as listed */
+    pub edge_triangles: Vec<usize>,
+    /* triangles incident to each vertex */
+    pub vertex_triangles: Vec<Vec<usize>>,
+    /* elevation of each vertex, empty unless every vertex has one */
+    pub z: Vec<f64>,
 }
 
 impl Triangulation {
     pub fn from(coordinates: Vec<f64>, triangles: Vec<usize>) -> Self {
+        let attributes = vec![0.0; triangles.len() / 3];
+        let vertex_markers = vec![0; coordinates.len() / 2];
         Self {
             coordinates: coordinates,
             triangles: triangles,
+            attributes,
+            vertex_markers,
+            boundary_edges: Vec::new(),
+            boundary_markers: Vec::new(),
+            neighbors: Vec::new(),
+            edges: Vec::new(),
+            edge_triangles: Vec::new(),
+            vertex_triangles: Vec::new(),
+            z: Vec::new(),
+        }
+    }
+
+    /**
+     * Smallest angle of each triangle, in degrees.
+     */
+    pub fn min_angles(&self) -> Vec<f64> {
+        self.triangles
+            .chunks(3)
+            .map(|triangle| {
+                let lengths = self.edge_lengths(triangle);
+                let mut min_angle = f64::INFINITY;
+                for corner in 0..3 {
+                    /* law of cosines, the opposite edge of a corner being the next one */
+                    let (opposite, side1, side2) = (
+                        lengths[(corner + 1) % 3],
+                        lengths[corner],
+                        lengths[(corner + 2) % 3],
+                    );
+                    let cosine =
+                        (side1.powi(2) + side2.powi(2) - opposite.powi(2)) / (2.0 * side1 * side2);
+                    min_angle = min_angle.min(cosine.clamp(-1.0, 1.0).acos().to_degrees());
+                }
+                min_angle
+            })
+            .collect()
+    }
+
+    /**
+     * Ratio of the circumradius to the shortest edge of each triangle, the measure
+     * Delaunay refinement bounds. The equilateral triangle has the lowest, 1/sqrt(3).
+     */
+    pub fn radius_edge_ratios(&self) -> Vec<f64> {
+        self.triangles
+            .chunks(3)
+            .map(|triangle| {
+                let lengths = self.edge_lengths(triangle);
+                let (a, b, c) = (lengths[0], lengths[1], lengths[2]);
+                let double_area =
+                    ((a + b + c) * (b + c - a) * (a + c - b) * (a + b - c)).sqrt() / 2.0;
+                let circumradius = a * b * c / (2.0 * double_area);
+                circumradius / a.min(b).min(c)
+            })
+            .collect()
+    }
+
+    /**
+     * Angle between each triangle and the horizontal, in degrees, as lifted by `z`.
+     * Empty unless the vertices have elevations.
+     */
+    pub fn slopes(&self) -> Vec<f64> {
+        self.lifted_triangles()
+            .iter()
+            .map(|triangle| triangle.slope().unwrap())
+            .collect()
+    }
+
+    /**
+     * Compass direction each triangle faces downhill, in degrees clockwise from
+     * the y axis, None for flat triangles. Empty unless the vertices have elevations.
+     */
+    pub fn aspects(&self) -> Vec<Option<f64>> {
+        self.lifted_triangles()
+            .iter()
+            .map(|triangle| triangle.aspect())
+            .collect()
+    }
+
+    /* triangles with their vertices at their elevations, none without `z` */
+    fn lifted_triangles(&self) -> Vec<Triangle> {
+        if self.z.is_empty() {
+            return Vec::new();
+        }
+        let vertices: Vec<Rc<Vertex>> = (0..self.coordinates.len() / 2)
+            .map(|index| {
+                Rc::new(Vertex::new_with_z(
+                    self.coordinates[index * 2],
+                    self.coordinates[index * 2 + 1],
+                    self.z[index],
+                ))
+            })
+            .collect();
+        self.triangles
+            .chunks(3)
+            .map(|triangle| {
+                Triangle::new(
+                    &vertices[triangle[0]],
+                    &vertices[triangle[1]],
+                    &vertices[triangle[2]],
+                )
+            })
+            .collect()
+    }
+
+    /* lengths of the edges from each corner to the next one */
+    fn edge_lengths(&self, triangle: &[usize]) -> [f64; 3] {
+        let mut lengths = [0.0; 3];
+        for corner in 0..3 {
+            let (v1, v2) = (triangle[corner], triangle[(corner + 1) % 3]);
+            lengths[corner] = (self.coordinates[v2 * 2] - self.coordinates[v1 * 2])
+                .hypot(self.coordinates[v2 * 2 + 1] - self.coordinates[v1 * 2 + 1]);
         }
+        lengths
+    }
+
+    /**
+     * Isolines of the field given by one value per vertex, such as `z`, at each
+     * of the levels in turn.
+     */
+    pub fn contours(&self, values: &[f64], levels: &[f64]) -> Vec<Contour> {
+        levels
+            .iter()
+            .flat_map(|level| Contour::trace(self, values, *level))
+            .collect()
     }
 }
 
@@ -36,3 +188,160 @@ impl fmt::Display for Triangulation {
         return write!(formatter, "");
     }
 }
+
+/**
+ * Reads back the text written by Display: a "Coordinates" section with a pair
+ * of numbers per line, then a "Triangles" section with three vertex indices per
+ * line. Blank lines are skipped.
+ */
+impl FromStr for Triangulation {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        let mut coordinates: Vec<f64> = Vec::new();
+        let mut triangles: Vec<usize> = Vec::new();
+        let mut section: Option<&str> = None;
+        let mut has_triangles = false;
+
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            match tokens.as_slice() {
+                [] => continue,
+                ["Coordinates"] if section.is_none() => section = Some("Coordinates"),
+                ["Triangles"] if section == Some("Coordinates") => {
+                    section = Some("Triangles");
+                    has_triangles = true;
+                }
+                _ => match section {
+                    None => {
+                        return Err(ParseError::new(
+                            line_number,
+                            "expected the Coordinates section",
+                        ))
+                    }
+                    Some("Coordinates") => {
+                        if tokens.len() != 2 {
+                            return Err(ParseError::new(
+                                line_number,
+                                &format!("expected 2 coordinates, found {}", tokens.len()),
+                            ));
+                        }
+                        for token in tokens {
+                            match token.parse::<f64>() {
+                                Ok(number) => coordinates.push(number),
+                                Err(_) => {
+                                    return Err(ParseError::new(
+                                        line_number,
+                                        &format!("invalid number '{}'", token),
+                                    ))
+                                }
+                            }
+                        }
+                    }
+                    _ => {
+                        if tokens.len() != 3 {
+                            return Err(ParseError::new(
+                                line_number,
+                                &format!("expected 3 vertex indices, found {}", tokens.len()),
+                            ));
+                        }
+                        for token in tokens {
+                            match token.parse::<usize>() {
+                                Ok(vertex) if vertex < coordinates.len() / 2 => {
+                                    triangles.push(vertex)
+                                }
+                                Ok(vertex) => {
+                                    return Err(ParseError::new(
+                                        line_number,
+                                        &format!("index {} out of range", vertex),
+                                    ))
+                                }
+                                Err(_) => {
+                                    return Err(ParseError::new(
+                                        line_number,
+                                        &format!("invalid index '{}'", token),
+                                    ))
+                                }
+                            }
+                        }
+                    }
+                },
+            }
+        }
+
+        if !has_triangles {
+            return Err(ParseError::new(0, "expected the Triangles section"));
+        }
+        Ok(Self::from(coordinates, triangles))
+    }
+}
+
+#[cfg(test)]
+mod from_str {
+    use super::*;
+
+    #[test]
+    fn test_reads_display() {
+        let triangulation =
+            Triangulation::from(vec![0.0, 0.0, 1.5, 0.0, 0.25, -1e-3], vec![0, 1, 2]);
+        let read: Triangulation = triangulation.to_string().parse().unwrap();
+        assert_eq!(read.coordinates, triangulation.coordinates);
+        assert_eq!(read.triangles, triangulation.triangles);
+    }
+
+    #[test]
+    fn test_reports_line_of_errors() {
+        let text = "Coordinates\n0 0\n1 0\n0 1\n\nTriangles\n0 1 3\n";
+        let error = text.parse::<Triangulation>().err().unwrap();
+        assert_eq!(error.to_string(), "line 7: index 3 out of range");
+
+        let error = "Coordinates\n0 x\n".parse::<Triangulation>().err().unwrap();
+        assert_eq!(error.line, 2);
+
+        let error = "Coordinates\n0 0\n".parse::<Triangulation>().err().unwrap();
+        assert_eq!(error.line, 0);
+    }
+}
+
+#[cfg(test)]
+mod quality {
+    use super::*;
+
+    #[test]
+    fn test_right_triangle() {
+        let triangulation = Triangulation::from(vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0], vec![0, 1, 2]);
+        assert!((triangulation.min_angles()[0] - 45.0).abs() < 1e-9);
+        assert!((triangulation.radius_edge_ratios()[0] - 0.5_f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_equilateral_triangle() {
+        let height = 3.0_f64.sqrt() / 2.0;
+        let triangulation =
+            Triangulation::from(vec![0.0, 0.0, 1.0, 0.0, 0.5, height], vec![0, 1, 2]);
+        assert!((triangulation.min_angles()[0] - 60.0).abs() < 1e-9);
+        assert!((triangulation.radius_edge_ratios()[0] - 1.0 / 3.0_f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_slopes_and_aspects() {
+        /* rising by 1 eastward over a unit step, then a flat triangle */
+        let mut triangulation = Triangulation::from(
+            vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+            vec![0, 1, 2, 1, 3, 2],
+        );
+        assert!(triangulation.slopes().is_empty());
+        assert!(triangulation.aspects().is_empty());
+
+        triangulation.z = vec![0.0, 1.0, 0.0, 1.0];
+        let slopes = triangulation.slopes();
+        assert!((slopes[0] - 45.0).abs() < 1e-9);
+        /* facing downhill to the west */
+        assert!((triangulation.aspects()[0].unwrap() - 270.0).abs() < 1e-9);
+
+        triangulation.z = vec![2.0; 4];
+        assert_eq!(triangulation.slopes(), vec![0.0, 0.0]);
+        assert_eq!(triangulation.aspects(), vec![None, None]);
+    }
+}
@@ -1,8 +1,20 @@
+use crate::alpha_shape::*;
 use crate::continence::*;
+use crate::convex_hull::*;
+use crate::location::*;
 use crate::orientation::*;
+use crate::parse_error::*;
+use crate::polygon::*;
+use crate::proximity::*;
+use crate::snapshot::*;
+use crate::svg_format::*;
 use crate::triangle::*;
 use crate::triangulation::*;
 use crate::vertex::*;
+use crate::voronoi::*;
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::BinaryHeap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fmt;
@@ -28,6 +40,13 @@ pub struct Triangulator {
     triangles: HashSet<Rc<Triangle>>,
     conflict_map: HashMap<Rc<Triangle>, Rc<Vertex>>,
     adjacency: HashMap<(Rc<Vertex>, Rc<Vertex>), Rc<Triangle>>,
+    segments: HashMap<(Rc<Vertex>, Rc<Vertex>), i32>,
+    vertex_markers: HashMap<Rc<Vertex>, i32>,
+    vertex_values: HashMap<Rc<Vertex>, f64>,
+    regions: Vec<(Rc<Vertex>, f64)>,
+    /* every vertex given or created, in order of arrival, with its index */
+    indexed_vertices: Vec<Rc<Vertex>>,
+    vertex_indices: HashMap<Rc<Vertex>, usize>,
 }
 
 impl fmt::Display for Triangulator {
@@ -56,7 +75,6 @@ impl Triangulator {
     /*
        TODO:
            - implement constrained delaunay triangulation
-           - include segments as constraint
     */
     pub fn new() -> Self {
         Self {
@@ -64,25 +82,55 @@ impl Triangulator {
             triangles: HashSet::new(),
             conflict_map: HashMap::new(),
             adjacency: HashMap::new(),
+            segments: HashMap::new(),
+            vertex_markers: HashMap::new(),
+            vertex_values: HashMap::new(),
+            regions: Vec::new(),
+            indexed_vertices: Vec::new(),
+            vertex_indices: HashMap::new(),
         }
     }
 
     pub fn from_vertices(vertices: Vec<Rc<Vertex>>) -> Self {
-        Self {
-            vertices: vertices,
-            triangles: HashSet::new(),
-            conflict_map: HashMap::new(),
-            adjacency: HashMap::new(),
+        let mut triangulator = Self::new();
+        for vertex in vertices.iter() {
+            triangulator.index_vertex(vertex);
         }
+        triangulator.vertices = vertices;
+        triangulator
     }
 
     pub fn from_coordinates(vertices_coordinates: Vec<f64>) -> Self {
-        Self {
-            vertices: Vertex::from_coordinates(vertices_coordinates),
-            triangles: HashSet::new(),
-            conflict_map: HashMap::new(),
-            adjacency: HashMap::new(),
+        Self::from_vertices(Vertex::from_coordinates(vertices_coordinates))
+    }
+
+    /**
+     * Builds from triples of x,y,z coordinates. Elevations do not take part in the
+     * triangulation, they are kept through export and for terrain computations.
+     */
+    pub fn from_coordinates_3d(vertices_coordinates: Vec<f64>) -> Self {
+        Self::from_vertices(Vertex::from_coordinates_3d(vertices_coordinates))
+    }
+
+    /**
+     * Whether `triangulate` can start: a mesh exists already, or the vertices
+     * given span a triangle, at least three distinct ones not all on a line.
+     */
+    pub fn spans_triangle(&self) -> bool {
+        if !self.triangles.is_empty() {
+            return true;
         }
+        let first = match self.vertices.first() {
+            Some(vertex) => vertex,
+            None => return false,
+        };
+        let second = match self.vertices.iter().find(|vertex| *vertex != first) {
+            Some(vertex) => vertex,
+            None => return false,
+        };
+        self.vertices
+            .iter()
+            .any(|vertex| orient_2d(first, second, vertex) != Orientation::Colinear)
     }
 
     pub fn triangulate(&mut self) {
@@ -91,54 +139,252 @@ impl Triangulator {
         if should_init {
             self.init();
         }
-        while self.conflict_map.len() > 0 {
-            self.handle_conflict();
+        loop {
+            while self.conflict_map.len() > 0 {
+                self.handle_conflict();
+            }
+
+            /*
+               A vertex whose conflicting triangle was dug out goes back to the
+               vertices list, but only new triangles are searched for conflicts.
+               If none of them encircles it, it is inserted from scratch.
+            */
+            match self.vertices.pop() {
+                Some(vertex) => {
+                    if !self.contains_vertex(&vertex) {
+                        self.insert_unconstrained_vertex(vertex);
+                    }
+                }
+                None => break,
+            }
         }
+
+        /* vertices left pending may have taken the edges of segments */
+        self.recover_segments();
     }
 
     /**
      * Vertex list must define successive connected edges and a closed boundary.
+     * The boundary is inserted as constrained segments and every triangle enclosed
+     * by it is removed, no matter how many triangles lie inside.
      */
     pub fn insert_hole(&mut self, vertex_list: Vec<Rc<Vertex>>) {
-        let mut inner_edges: HashSet<(Rc<Vertex>, Rc<Vertex>)> = HashSet::new();
+        let mut boundary: Vec<(Rc<Vertex>, Rc<Vertex>)> = Vec::new();
+        let mut double_area = 0.0;
         for index in 0..vertex_list.len() {
             let v1 = vertex_list.get(index).unwrap();
             let v2 = match vertex_list.get(index + 1) {
                 Some(vertex) => vertex,
-                None => vertex_list.get(0).unwrap(),
+                None => vertex_list.first().unwrap(),
             };
-            inner_edges.insert((Rc::clone(v1), Rc::clone(v2)));
+            double_area += v1.x * v2.y - v2.x * v1.y;
+            boundary.push((Rc::clone(v1), Rc::clone(v2)));
         }
 
-        for vertex in vertex_list.iter() {
-            self.insert_vertex(Rc::clone(vertex));
+        for (v1, v2) in boundary.iter() {
+            self.insert_segment(Rc::clone(v1), Rc::clone(v2));
         }
 
-        let ghost_vertex = Rc::new(Vertex::new_ghost());
-        for (v1, v2) in inner_edges.iter() {
-            if let Some(inner_triangle) = self.adjacency.get(&(Rc::clone(v1), Rc::clone(v2))) {
-                self.remove_triangle(&Rc::clone(inner_triangle));
+        /* the hole lies at the left side of a counterclockwise boundary */
+        let mut seed_triangles: Vec<Rc<Triangle>> = Vec::new();
+        for (v1, v2) in boundary.iter() {
+            let v_next = self.subsegment_from(v1, v2);
+            let inner_edge = if double_area > 0.0 {
+                (Rc::clone(v1), v_next)
+            } else {
+                (v_next, Rc::clone(v1))
+            };
+            if let Some(triangle) = self.adjacency.get(&inner_edge) {
+                seed_triangles.push(Rc::clone(triangle));
             }
-
-            let ghost_triangle = Rc::new(Triangle::new(v1, v2, &ghost_vertex));
-            self.include_triangle(&ghost_triangle);
         }
+
+        self.remove_enclosed_triangles(seed_triangles);
     }
 
-    pub fn insert_vertex(&mut self, vertex: Rc<Vertex>) {
-        if let Some(conflicting_triangle) = self
+    /**
+     * Removes every triangle reachable from the seed without crossing a constrained
+     * segment, as Triangle does with hole points. The hole boundary must be inserted
+     * beforehand with `insert_segment`.
+     */
+    pub fn insert_hole_seed(&mut self, seed: Rc<Vertex>) {
+        let seed_triangle = match self.find_containing_triangle(&seed) {
+            Some(triangle) => triangle,
+            None => panic!("Expected to find triangle containing hole seed"),
+        };
+
+        self.remove_enclosed_triangles(vec![seed_triangle]);
+    }
+
+    /**
+     * Inserts closed rings as constrained segments and removes every triangle
+     * outside of them. A point is inside if a ray leaving it crosses the rings an
+     * odd number of times, so outer rings may be concave, and holes and islands
+     * within holes need no orientation. Ghost triangles then follow the outer
+     * rings instead of the convex hull, and so does `convex_hull`.
+     */
+    pub fn insert_boundary(&mut self, rings: &[Vec<Rc<Vertex>>]) {
+        for ring in rings.iter() {
+            for (index, v1) in ring.iter().enumerate() {
+                let v2 = &ring[(index + 1) % ring.len()];
+                self.insert_segment(Rc::clone(v1), Rc::clone(v2));
+            }
+        }
+
+        let ring_coordinates: Vec<Vec<f64>> = rings
+            .iter()
+            .map(|ring| {
+                ring.iter()
+                    .flat_map(|vertex| vec![vertex.x, vertex.y])
+                    .collect()
+            })
+            .collect();
+
+        /* each part enclosed by segments is wholly inside or outside */
+        let mut visited: HashSet<Rc<Triangle>> = HashSet::new();
+        let mut outside_seeds: Vec<Rc<Triangle>> = Vec::new();
+        for triangle in self
             .triangles
             .iter()
-            .find(|triangle| triangle.encircles(&vertex) == Continence::Inside)
+            .filter(|triangle| !triangle.is_ghost())
         {
-            let conflicting_triangle = Rc::clone(conflicting_triangle);
-            self.triangles.remove(&conflicting_triangle);
-            self.conflict_map.insert(conflicting_triangle, vertex);
-            self.handle_conflict();
-            return;
+            if visited.contains(triangle) {
+                continue;
+            }
+            visited.extend(self.flood_fill(vec![Rc::clone(triangle)]));
+
+            let centroid = Vertex::mean(&[&triangle.v1, &triangle.v2, &triangle.v3]);
+            let crossings = ring_coordinates
+                .iter()
+                .filter(|ring| polygon_contains(ring, centroid.x, centroid.y))
+                .count();
+            if crossings % 2 == 0 {
+                outside_seeds.push(Rc::clone(triangle));
+            }
+        }
+
+        self.remove_enclosed_triangles(outside_seeds);
+    }
+
+    /**
+     * Tags every triangle reachable from the seed without crossing a constrained
+     * segment with the regional attribute, as Triangle does with regional points.
+     * If a maximum area is given, the region is refined by inserting the centroid
+     * of its largest triangle until every triangle fits the constraint.
+     * Triangles reached by several regions keep the attribute of the last one.
+     */
+    pub fn insert_region(&mut self, seed: Rc<Vertex>, attribute: f64, max_area: Option<f64>) {
+        self.regions.push((Rc::clone(&seed), attribute));
+
+        let max_area = match max_area {
+            Some(max_area) => max_area,
+            None => return,
         };
 
-        panic!("Expected to find conflicting triangle to insert vertex");
+        loop {
+            let seed_triangle = match self.find_containing_triangle(&seed) {
+                Some(triangle) => triangle,
+                None => panic!("Expected to find triangle containing region seed"),
+            };
+
+            let largest_triangle = self
+                .flood_fill(vec![seed_triangle])
+                .into_iter()
+                .filter(|triangle| triangle.area() > max_area)
+                .max_by(|t1, t2| t1.area().partial_cmp(&t2.area()).unwrap());
+
+            match largest_triangle {
+                Some(triangle) => {
+                    let centroid =
+                        Rc::new(Vertex::mean(&[&triangle.v1, &triangle.v2, &triangle.v3]));
+                    self.insert_vertex(centroid);
+                }
+                None => break,
+            }
+        }
+    }
+
+    /**
+     * Inserts a constrained segment between two vertices, which are inserted first
+     * when missing. If the segment is not an edge of the triangulation, it is split
+     * at its midpoint until every subsegment is recovered as an edge.
+     */
+    pub fn insert_segment(&mut self, v1: Rc<Vertex>, v2: Rc<Vertex>) {
+        self.insert_marked_segment(v1, v2, 0);
+    }
+
+    /**
+     * Inserts a constrained segment tagged with a boundary marker, such as an inlet,
+     * an outlet or a wall. Subsegments and Steiner points created on the segment
+     * inherit the marker, as well as its endpoints when they have no marker yet.
+     * Marker 0 stands for no marker.
+     */
+    pub fn insert_marked_segment(&mut self, v1: Rc<Vertex>, v2: Rc<Vertex>, marker: i32) {
+        if !self.contains_vertex(&v1) {
+            self.insert_vertex(Rc::clone(&v1));
+        }
+        if !self.contains_vertex(&v2) {
+            self.insert_vertex(Rc::clone(&v2));
+        }
+
+        self.mark_unmarked_vertex(&v1, marker);
+        self.mark_unmarked_vertex(&v2, marker);
+
+        if self.is_segment(&v1, &v2) {
+            self.segments.remove(&(Rc::clone(&v2), Rc::clone(&v1)));
+        }
+        self.segments.insert((v1, v2), marker);
+        self.recover_segments();
+    }
+
+    /**
+     * Tags a vertex with a boundary marker, overriding the marker it may have
+     * inherited from a segment. Marker 0 stands for no marker.
+     */
+    pub fn set_vertex_marker(&mut self, vertex: Rc<Vertex>, marker: i32) {
+        self.vertex_markers.insert(vertex, marker);
+    }
+
+    /**
+     * Attaches a scalar value to a vertex, to be interpolated over the mesh.
+     */
+    pub fn set_vertex_value(&mut self, vertex: Rc<Vertex>, value: f64) {
+        self.vertex_values.insert(vertex, value);
+    }
+
+    /**
+     * Attaches values to the vertices in order of arrival, as indexed by
+     * `export_preserving_order`.
+     */
+    pub fn set_vertex_values(&mut self, values: &[f64]) {
+        for (vertex, value) in self.indexed_vertices.iter().zip(values.iter()) {
+            self.vertex_values.insert(Rc::clone(vertex), *value);
+        }
+    }
+
+    /**
+     * Inserts a vertex into the triangulation. Constrained segments whose edges
+     * the vertex removes are split until recovered, so they stay in the mesh.
+     */
+    pub fn insert_vertex(&mut self, vertex: Rc<Vertex>) {
+        self.insert_unconstrained_vertex(vertex);
+        self.recover_segments();
+    }
+
+    /**
+     * Inserts a vertex by Bowyer-Watson, regardless of constrained segments.
+     */
+    fn insert_unconstrained_vertex(&mut self, vertex: Rc<Vertex>) {
+        self.register_vertex(&vertex);
+
+        match self.conflicting_triangle(&vertex) {
+            Some(conflicting_triangle) => {
+                self.triangles.remove(&conflicting_triangle);
+                self.conflict_map.insert(conflicting_triangle, vertex);
+                self.handle_conflict();
+            }
+            None => panic!("Expected to find conflicting triangle to insert vertex"),
+        }
     }
 
     pub fn delete_vertex(&mut self, vertex: Rc<Vertex>) {
@@ -152,6 +398,10 @@ impl Triangulator {
             return;
         }
 
+        if self.is_boundary_vertex(&vertex) {
+            panic!("Cannot delete vertex at boundary");
+        }
+
         /* Else removes triangles withe the specified vertex and inserts a  */
         let conflicting_triangles: Vec<Rc<Triangle>> = self
             .triangles
@@ -165,12 +415,6 @@ impl Triangulator {
             .cloned()
             .collect();
 
-        for triangle in conflicting_triangles.iter() {
-            if triangle.is_ghost() {
-                panic!("Cannot delete vertex at boundary");
-            }
-        }
-
         for triangle in conflicting_triangles.iter() {
             self.remove_triangle(triangle);
         }
@@ -183,7 +427,17 @@ impl Triangulator {
             vertices_set.insert(Rc::clone(&triangle.v3));
         }
 
-        let mut vertices_vec: Vec<Rc<Vertex>> = vertices_set
+        /* edges opposite to the vertex bound the cavity in counterclockwise direction */
+        let mut cavity_edges: HashSet<(Rc<Vertex>, Rc<Vertex>)> = HashSet::new();
+        for triangle in conflicting_triangles.iter() {
+            for (v1, v2) in triangle.edges() {
+                if v1 != vertex && v2 != vertex {
+                    cavity_edges.insert((v1, v2));
+                }
+            }
+        }
+
+        let vertices_vec: Vec<Rc<Vertex>> = vertices_set
             .iter()
             .filter(|&possible| *possible != vertex)
             .cloned()
@@ -192,21 +446,20 @@ impl Triangulator {
         let mut inner_triangulation = Self::from_vertices(vertices_vec);
         inner_triangulation.triangulate();
 
-        self.merge_triangles(inner_triangulation);
+        self.merge_triangles(inner_triangulation, &cavity_edges);
     }
 
+    /**
+     * Exports the solid triangles with vertices sorted by position.
+     */
     pub fn export(&self) -> Triangulation {
-        /* Separates solid triangles only */
-        let solid_triangles: HashSet<Rc<Triangle>> = self
+        /* HashSet will avoid duplicates */
+        let mut vertices_set: HashSet<Rc<Vertex>> = HashSet::new();
+        for triangle in self
             .triangles
             .iter()
             .filter(|triangle| !triangle.is_ghost())
-            .cloned()
-            .collect();
-
-        /* HashSet will avoid duplicates */
-        let mut vertices_set: HashSet<Rc<Vertex>> = HashSet::new();
-        for triangle in solid_triangles.iter() {
+        {
             vertices_set.insert(Rc::clone(&triangle.v1));
             vertices_set.insert(Rc::clone(&triangle.v2));
             vertices_set.insert(Rc::clone(&triangle.v3));
@@ -216,11 +469,196 @@ impl Triangulator {
         let mut vertices_vec: Vec<Rc<Vertex>> = vertices_set.iter().cloned().collect();
         vertices_vec.sort();
 
+        self.export_vertices(vertices_vec)
+    }
+
+    /**
+     * Exports the solid triangles keeping the indices of the input vertices, so that
+     * vertex `i` of the triangulation is the `i`-th vertex given to the triangulator.
+     * Steiner points follow in order of creation. Vertices left out of the mesh,
+     * as the ones deleted or inside holes, keep their coordinates but no triangle
+     * refers to them. So do repeated input vertices, as triangles refer to the
+     * first index given to the same coordinates.
+     */
+    pub fn export_preserving_order(&self) -> Triangulation {
+        self.export_vertices(self.indexed_vertices.clone())
+    }
+
+    /**
+     * Captures the whole state, ghost triangles, pending conflicts and
+     * constraints included, to be restored with `from_snapshot`.
+     */
+    pub fn snapshot(&self) -> TriangulatorSnapshot {
+        let mut vertices: Vec<Rc<Vertex>> = self.indexed_vertices.clone();
+        let mut indices: HashMap<Rc<Vertex>, usize> = self.vertex_indices.clone();
+        let mut index_of = |vertex: &Rc<Vertex>| -> usize {
+            if vertex.is_ghost {
+                return GHOST_VERTEX;
+            }
+            *indices.entry(Rc::clone(vertex)).or_insert_with(|| {
+                vertices.push(Rc::clone(vertex));
+                vertices.len() - 1
+            })
+        };
+
+        let mut triangles: Vec<usize> = Vec::new();
+        let mut conflicts: Vec<usize> = Vec::new();
+        let conflicting = self
+            .conflict_map
+            .iter()
+            .map(|(triangle, vertex)| (triangle, Some(vertex)));
+        for (triangle, vertex) in self
+            .triangles
+            .iter()
+            .map(|triangle| (triangle, None))
+            .chain(conflicting)
+        {
+            if let Some(vertex) = vertex {
+                conflicts.push(triangles.len() / 3);
+                conflicts.push(index_of(vertex));
+            }
+            triangles.push(index_of(&triangle.v1));
+            triangles.push(index_of(&triangle.v2));
+            triangles.push(index_of(&triangle.v3));
+        }
+        let pending_vertices: Vec<usize> = self.vertices.iter().map(&mut index_of).collect();
+
+        let mut segments: Vec<usize> = Vec::new();
+        let mut segment_markers: Vec<i32> = Vec::new();
+        for ((v1, v2), marker) in self.segments.iter() {
+            segments.push(index_of(v1));
+            segments.push(index_of(v2));
+            segment_markers.push(*marker);
+        }
+        let mut vertex_values: Vec<(usize, f64)> = self
+            .vertex_values
+            .iter()
+            .map(|(vertex, value)| (index_of(vertex), *value))
+            .collect();
+        vertex_values.sort_by_key(|(index, _)| *index);
+
+        let vertex_markers: Vec<i32> = vertices
+            .iter()
+            .map(|vertex| *self.vertex_markers.get(vertex).unwrap_or(&0))
+            .collect();
+        let regions: Vec<f64> = self
+            .regions
+            .iter()
+            .flat_map(|(seed, attribute)| vec![seed.x, seed.y, *attribute])
+            .collect();
+
+        TriangulatorSnapshot {
+            vertices: vertices
+                .iter()
+                .map(|vertex| Vertex {
+                    x: vertex.x,
+                    y: vertex.y,
+                    is_ghost: false,
+                    z: vertex.z,
+                })
+                .collect(),
+            triangles,
+            conflicts,
+            pending_vertices,
+            segments,
+            segment_markers,
+            vertex_markers,
+            vertex_values,
+            regions,
+        }
+    }
+
+    /**
+     * Restores a triangulator from a snapshot, checking first that its lists have
+     * the right lengths and refer to existing vertices and triangles.
+     */
+    pub fn from_snapshot(snapshot: TriangulatorSnapshot) -> Result<Self, ParseError> {
+        snapshot.validate()?;
+        let mut triangulator = Self::new();
+        let vertices: Vec<Rc<Vertex>> = snapshot.vertices.into_iter().map(Rc::new).collect();
+        for vertex in vertices.iter() {
+            triangulator.index_vertex(vertex);
+        }
+
+        let ghost_vertex = Rc::new(Vertex::new_ghost());
+        let vertex_at = |index: usize| -> Rc<Vertex> {
+            if index == GHOST_VERTEX {
+                Rc::clone(&ghost_vertex)
+            } else {
+                Rc::clone(&vertices[index])
+            }
+        };
+
+        let conflicts: HashMap<usize, usize> = snapshot
+            .conflicts
+            .chunks(2)
+            .map(|conflict| (conflict[0], conflict[1]))
+            .collect();
+        for (index, corners) in snapshot.triangles.chunks(3).enumerate() {
+            let triangle = Rc::new(Triangle::new(
+                &vertex_at(corners[0]),
+                &vertex_at(corners[1]),
+                &vertex_at(corners[2]),
+            ));
+            triangulator.include_inner_adjacency(&triangle);
+            match conflicts.get(&index) {
+                Some(vertex) => {
+                    triangulator
+                        .conflict_map
+                        .insert(triangle, vertex_at(*vertex));
+                }
+                None => {
+                    triangulator.triangles.insert(triangle);
+                }
+            }
+        }
+        triangulator.vertices = snapshot
+            .pending_vertices
+            .iter()
+            .map(|index| vertex_at(*index))
+            .collect();
+
+        for (segment, marker) in snapshot
+            .segments
+            .chunks(2)
+            .zip(snapshot.segment_markers.iter())
+        {
+            triangulator
+                .segments
+                .insert((vertex_at(segment[0]), vertex_at(segment[1])), *marker);
+        }
+        for (index, marker) in snapshot.vertex_markers.iter().enumerate() {
+            if *marker != 0 {
+                triangulator
+                    .vertex_markers
+                    .insert(vertex_at(index), *marker);
+            }
+        }
+        for (index, value) in snapshot.vertex_values.iter() {
+            triangulator.vertex_values.insert(vertex_at(*index), *value);
+        }
+        for region in snapshot.regions.chunks(3) {
+            triangulator
+                .regions
+                .push((Rc::new(Vertex::new(region[0], region[1])), region[2]));
+        }
+        Ok(triangulator)
+    }
+
+    fn export_vertices(&self, vertices_vec: Vec<Rc<Vertex>>) -> Triangulation {
+        /* Separates solid triangles only */
+        let solid_triangles: HashSet<Rc<Triangle>> = self
+            .triangles
+            .iter()
+            .filter(|triangle| !triangle.is_ghost())
+            .cloned()
+            .collect();
+
         /* mapping of vertex into its index */
         let mut vertices_index_mapping: HashMap<Rc<Vertex>, usize> = HashMap::new();
         for index in 0..vertices_vec.len() {
             let vertex = Rc::clone(vertices_vec.get(index).unwrap());
-            vertices_index_mapping.insert(vertex, index);
+            vertices_index_mapping.entry(vertex).or_insert(index);
         }
 
         let mut coordinates: Vec<f64> = Vec::new();
@@ -229,8 +667,13 @@ impl Triangulator {
             coordinates.push(vertex.y);
         }
 
+        let region_attributes = self.region_attributes();
+        let mut attributes: Vec<f64> = Vec::new();
+
         let mut triangle_index_array: Vec<usize> = Vec::new();
         for triangle in solid_triangles.iter() {
+            attributes.push(*region_attributes.get(triangle).unwrap_or(&0.0));
+
             let v1_index = vertices_index_mapping.get(&triangle.v1).unwrap();
             let v2_index = vertices_index_mapping.get(&triangle.v2).unwrap();
             let v3_index = vertices_index_mapping.get(&triangle.v3).unwrap();
@@ -251,48 +694,431 @@ impl Triangulator {
             }
         }
 
-        return Triangulation::from(coordinates, triangle_index_array);
-    }
+        /* the i-th neighbor lies across the edge from the i-th to the next vertex */
+        let mut triangle_indices: HashMap<Rc<Triangle>, usize> = HashMap::new();
+        for (index, triangle) in solid_triangles.iter().enumerate() {
+            triangle_indices.insert(Rc::clone(triangle), index);
+        }
+        let mut neighbors: Vec<usize> = Vec::new();
+        for triangle in triangle_index_array.chunks(3) {
+            for position in 0..3 {
+                let v1 = vertices_vec.get(triangle[position]).unwrap();
+                let v2 = vertices_vec.get(triangle[(position + 1) % 3]).unwrap();
+                let neighbor = self
+                    .adjacency
+                    .get(&(Rc::clone(v2), Rc::clone(v1)))
+                    .and_then(|neighbor| triangle_indices.get(neighbor))
+                    .cloned()
+                    .unwrap_or(NO_NEIGHBOR);
+                neighbors.push(neighbor);
+            }
+        }
 
-    fn vertices_size(&self) -> usize {
-        let mut vertices_set: HashSet<Rc<Vertex>> = self.vertices.iter().cloned().collect();
-        for triangle in self.triangles.iter() {
-            vertices_set.insert(Rc::clone(&triangle.v1));
-            vertices_set.insert(Rc::clone(&triangle.v2));
-            vertices_set.insert(Rc::clone(&triangle.v3));
+        /* unique edges, keeping the direction of the first incident triangle */
+        let mut edges: Vec<usize> = Vec::new();
+        let mut edge_triangles: Vec<usize> = Vec::new();
+        let mut vertex_triangles: Vec<Vec<usize>> = vec![Vec::new(); vertices_vec.len()];
+        for (index, triangle) in triangle_index_array.chunks(3).enumerate() {
+            for position in 0..3 {
+                vertex_triangles[triangle[position]].push(index);
+
+                let neighbor = neighbors[index * 3 + position];
+                if neighbor == NO_NEIGHBOR || index < neighbor {
+                    edges.push(triangle[position]);
+                    edges.push(triangle[(position + 1) % 3]);
+                    edge_triangles.push(index);
+                    edge_triangles.push(neighbor);
+                }
+            }
         }
 
-        return vertices_set
-            .iter()
-            .filter(|vertex| !vertex.is_ghost)
-            .count();
+        let mut vertex_markers: Vec<i32> = Vec::new();
+        for vertex in vertices_vec.iter() {
+            vertex_markers.push(*self.vertex_markers.get(vertex).unwrap_or(&0));
+        }
+
+        /* constrained subsegments and edges facing ghost triangles bound the mesh */
+        let mut boundary_edges: Vec<usize> = Vec::new();
+        let mut boundary_markers: Vec<i32> = Vec::new();
+        for triangle in solid_triangles.iter() {
+            for (v1, v2) in triangle.edges() {
+                let is_ghost_edge = match self.adjacency.get(&(Rc::clone(&v2), Rc::clone(&v1))) {
+                    Some(neighbor) => neighbor.is_ghost(),
+                    None => true,
+                };
+                let marker = self.segment_marker(&v1, &v2);
+
+                /* interior segments are shared by two solid triangles, but listed once */
+                let is_listed = !is_ghost_edge && v1 > v2;
+                if (is_ghost_edge || marker.is_some()) && !is_listed {
+                    boundary_edges.push(*vertices_index_mapping.get(&v1).unwrap());
+                    boundary_edges.push(*vertices_index_mapping.get(&v2).unwrap());
+                    boundary_markers.push(marker.unwrap_or(0));
+                }
+            }
+        }
+
+        let mut triangulation = Triangulation::from(coordinates, triangle_index_array);
+        triangulation.attributes = attributes;
+        triangulation.vertex_markers = vertex_markers;
+        triangulation.boundary_edges = boundary_edges;
+        triangulation.boundary_markers = boundary_markers;
+        triangulation.neighbors = neighbors;
+        triangulation.edges = edges;
+        triangulation.edge_triangles = edge_triangles;
+        triangulation.vertex_triangles = vertex_triangles;
+        let elevations: Option<Vec<f64>> = vertices_vec.iter().map(|vertex| vertex.z).collect();
+        triangulation.z = elevations.unwrap_or_default();
+        triangulation
     }
 
-    fn triangles_size(&self) -> usize {
-        let mut triangles_set: HashSet<Rc<Triangle>> = self.triangles.iter().cloned().collect();
+    /**
+     * Derives the Voronoi diagram, with one cell for each vertex of the mesh,
+     * in the same order as `export_preserving_order`.
+     */
+    pub fn voronoi(&self) -> Voronoi {
+        Voronoi::from_adjacency(&self.indexed_vertices, &self.adjacency)
+    }
 
-        for triangle in self.conflict_map.keys() {
-            triangles_set.insert(Rc::clone(triangle));
-            triangles_set.insert(Rc::clone(triangle));
-            triangles_set.insert(Rc::clone(triangle));
+    /**
+     * Convex hull of the vertices in the mesh, convex even after `insert_boundary`
+     * with a concave outer ring.
+     */
+    pub fn convex_hull(&self) -> ConvexHull {
+        ConvexHull::from_adjacency(&self.adjacency)
+    }
+
+    /**
+     * Draws the current state, ghost triangles and pending conflicts included,
+     * as an SVG picture. Meant for debugging, in place of the Display dump.
+     */
+    pub fn debug_svg(&self, style: &SvgStyle) -> String {
+        write_debug_svg(
+            &self.indexed_vertices,
+            &self.triangles,
+            &self.conflict_map,
+            &self.segments,
+            style,
+        )
+    }
+
+    /**
+     * Finds where the point falls, walking from triangle to triangle towards it
+     * across the edges that leave it on their clockwise side. Returns None if
+     * nothing is triangulated yet.
+     */
+    pub fn locate(&self, x: f64, y: f64) -> Option<Location> {
+        let point = Vertex::new(x, y);
+        match self.walk_to(&point) {
+            Some(triangle) => Some(Self::location_in(&triangle, &point)),
+            None => self.nearest_boundary_edge(&point),
         }
+    }
 
-        return triangles_set
-            .iter()
-            .filter(|triangle| !triangle.is_ghost())
-            .count();
+    /**
+     * Nearest vertex to the point, as its index in `export_preserving_order`
+     * and its distance.
+     */
+    pub fn nearest(&self, x: f64, y: f64) -> Option<(usize, f64)> {
+        self.nearest_vertices(x, y, |count, _| count < 1).pop()
     }
 
-    fn init(&mut self) {
-        let ghost_vertex = Rc::new(Vertex::new_ghost());
+    /**
+     * The k nearest vertices to the point, by increasing distance.
+     */
+    pub fn k_nearest(&self, x: f64, y: f64, k: usize) -> Vec<(usize, f64)> {
+        self.nearest_vertices(x, y, |count, _| count < k)
+    }
 
-        let mut v3 = self.vertices.pop().unwrap();
-        let mut v2 = self.vertices.pop().unwrap();
-        let mut v1 = self.vertices.pop().unwrap();
+    /**
+     * Vertices within the radius from the point, by increasing distance.
+     */
+    pub fn within_radius(&self, x: f64, y: f64, radius: f64) -> Vec<(usize, f64)> {
+        self.nearest_vertices(x, y, |_, distance| distance <= radius)
+    }
 
-        /* Loops until 3 non colinear vertices are found */
-        loop {
-            match orient_2d(&v1, &v2, &v3) {
+    /**
+     * Barycentric interpolation of the vertex values over the triangle containing
+     * the point. Returns None outside the mesh or if a vertex has no value.
+     */
+    pub fn interpolate_linear(&self, x: f64, y: f64) -> Option<f64> {
+        let point = Vertex::new(x, y);
+        let triangle = self.walk_to(&point)?;
+        let (v1, v2, v3) = (&triangle.v1, &triangle.v2, &triangle.v3);
+        let doubled_area = |a: &Vertex, b: &Vertex, c: &Vertex| {
+            (b.x - a.x) * (c.y - a.y) - (c.x - a.x) * (b.y - a.y)
+        };
+
+        let total = doubled_area(v1, v2, v3);
+        let weights = [
+            doubled_area(&point, v2, v3) / total,
+            doubled_area(v1, &point, v3) / total,
+            doubled_area(v1, v2, &point) / total,
+        ];
+        let mut value = 0.0;
+        for (vertex, weight) in [v1, v2, v3].iter().zip(weights.iter()) {
+            value += weight * self.vertex_values.get(*vertex)?;
+        }
+        Some(value)
+    }
+
+    /**
+     * Sibson natural neighbour interpolation. The point is virtually inserted: the
+     * triangles whose circumcircle contains it form the Bowyer-Watson cavity, whose
+     * vertices are its natural neighbours. Each one weighs the share of the new
+     * Voronoi cell of the point taken from its own cell. On the convex hull, where
+     * the new cell is unbounded, it falls back to linear interpolation.
+     */
+    pub fn interpolate_natural_neighbor(&self, x: f64, y: f64) -> Option<f64> {
+        let point = Rc::new(Vertex::new(x, y));
+        let start_triangle = self.walk_to(&point)?;
+        if let Location::OnVertex(vertex) = Self::location_in(&start_triangle, &point) {
+            return self.vertex_values.get(&vertex).copied();
+        }
+
+        let mut cavity: HashSet<Rc<Triangle>> = HashSet::new();
+        let mut pending: Vec<Rc<Triangle>> = vec![start_triangle];
+        while let Some(triangle) = pending.pop() {
+            if triangle.encircles(&point) != Continence::Inside
+                || !cavity.insert(Rc::clone(&triangle))
+            {
+                continue;
+            }
+            if triangle.is_ghost() {
+                return self.interpolate_linear(x, y);
+            }
+            for (v1, v2) in triangle.edges() {
+                if let Some(neighbor) = self.adjacency.get(&(v2, v1)) {
+                    pending.push(Rc::clone(neighbor));
+                }
+            }
+        }
+
+        /* cavity boundary, in counterclockwise order */
+        let mut boundary: HashMap<Rc<Vertex>, Rc<Vertex>> = HashMap::new();
+        for triangle in cavity.iter() {
+            for (v1, v2) in triangle.edges() {
+                let is_boundary = match self.adjacency.get(&(Rc::clone(&v2), Rc::clone(&v1))) {
+                    Some(neighbor) => !cavity.contains(neighbor),
+                    None => true,
+                };
+                if is_boundary {
+                    boundary.insert(v1, v2);
+                }
+            }
+        }
+        let first = Rc::clone(boundary.keys().next()?);
+        let mut neighbors: Vec<Rc<Vertex>> = vec![Rc::clone(&first)];
+        while let Some(next) = boundary.get(neighbors.last()?) {
+            if *next == first || neighbors.len() > boundary.len() {
+                break;
+            }
+            neighbors.push(Rc::clone(next));
+        }
+
+        let size = neighbors.len();
+        let mut new_cell: Vec<f64> = Vec::new();
+        for index in 0..size {
+            let circumcenter =
+                Triangle::new(&point, &neighbors[index], &neighbors[(index + 1) % size])
+                    .circumcenter();
+            new_cell.push(circumcenter.x);
+            new_cell.push(circumcenter.y);
+        }
+        let new_cell_area = polygon_area(&new_cell);
+
+        let mut value = 0.0;
+        for neighbor in neighbors.iter() {
+            let neighbor_triangle =
+                &self.adjacency[&(Rc::clone(neighbor), Rc::clone(&boundary[neighbor]))];
+            let mut stolen = new_cell.clone();
+            for (other, _) in self.vertex_neighbors(neighbor, neighbor_triangle) {
+                stolen = clip_by_bisector(&stolen, neighbor, &other);
+            }
+            value += polygon_area(&stolen) / new_cell_area * self.vertex_values.get(neighbor)?;
+        }
+        Some(value)
+    }
+
+    /**
+     * Total area of the terrain surface. None if a vertex has no elevation.
+     */
+    pub fn surface_area(&self) -> Option<f64> {
+        self.triangles
+            .iter()
+            .filter(|triangle| !triangle.is_ghost())
+            .map(|triangle| triangle.surface_area())
+            .sum()
+    }
+
+    /**
+     * Volume of the terrain above the datum. None if a vertex has no elevation.
+     */
+    pub fn volume_above(&self, datum: f64) -> Option<f64> {
+        self.triangles
+            .iter()
+            .filter(|triangle| !triangle.is_ghost())
+            .map(|triangle| triangle.volume_above(datum))
+            .sum()
+    }
+
+    /**
+     * Alpha shape made of the triangles whose circumradius is at most alpha.
+     */
+    pub fn alpha_shape(&self, alpha: f64) -> AlphaShape {
+        AlphaShape::from_adjacency(&self.triangles, &self.adjacency, alpha)
+    }
+
+    /**
+     * Smallest alpha giving a single polygon that covers every vertex, if any.
+     */
+    pub fn optimal_alpha(&self) -> Option<f64> {
+        AlphaShape::optimal_alpha(&self.triangles, &self.adjacency)
+    }
+
+    /**
+     * Delaunay edges as pairs of indices in `export_preserving_order`, with
+     * their length. The proximity graphs below are subgraphs of it.
+     */
+    pub fn delaunay_edges(&self) -> Vec<(usize, usize, f64)> {
+        delaunay_edges(&self.adjacency, &self.vertex_indices)
+    }
+
+    pub fn gabriel_graph(&self) -> Vec<(usize, usize, f64)> {
+        gabriel_edges(&self.adjacency, &self.vertex_indices)
+    }
+
+    pub fn relative_neighborhood_graph(&self) -> Vec<(usize, usize, f64)> {
+        relative_neighborhood_edges(&self.adjacency, &self.vertex_indices)
+    }
+
+    pub fn minimum_spanning_tree(&self) -> Vec<(usize, usize, f64)> {
+        minimum_spanning_tree_edges(&self.adjacency, &self.vertex_indices)
+    }
+
+    /**
+     * Largest circle holding no vertex, centered within the convex hull, as its
+     * center and radius.
+     */
+    pub fn largest_empty_circle(&self) -> Option<(Vertex, f64)> {
+        self.largest_empty_circle_in(&self.convex_hull().coordinates())
+    }
+
+    /**
+     * Largest circle holding no vertex, centered within the polygon, given by pairs
+     * of x,y coordinates in counterclockwise order. Within each Voronoi cell the
+     * distance to the site grows towards the cell boundary, so the center is a
+     * vertex of some cell clipped to the polygon: a circumcenter, a crossing of a
+     * Voronoi edge with the polygon or a corner of the polygon.
+     */
+    pub fn largest_empty_circle_in(&self, polygon: &[f64]) -> Option<(Vertex, f64)> {
+        let mut largest: Option<(Vertex, f64)> = None;
+        for cell in self.voronoi().cells.iter() {
+            for corner in cell.clip(polygon).chunks(2) {
+                let radius = (corner[0] - cell.site.x).hypot(corner[1] - cell.site.y);
+                if largest
+                    .as_ref()
+                    .is_none_or(|(_, largest_radius)| radius > *largest_radius)
+                {
+                    largest = Some((Vertex::new(corner[0], corner[1]), radius));
+                }
+            }
+        }
+        largest
+    }
+
+    /**
+     * Lloyd relaxation towards a centroidal Voronoi tessellation. Each vertex moves
+     * to the centroid of its Voronoi cell clipped to the convex hull, by deleting it
+     * and inserting the centroid. Vertices with unbounded cells, at the hull or
+     * around holes, and vertices on segments stay fixed, as well as vertices whose
+     * centroid falls into a hole. Stops once no vertex moves farther than the
+     * tolerance, returning the number of iterations run.
+     */
+    pub fn relax(&mut self, max_iterations: usize, tolerance: f64) -> usize {
+        for iteration in 0..max_iterations {
+            let hull_coordinates = self.convex_hull().coordinates();
+
+            let mut moves: Vec<(Rc<Vertex>, Rc<Vertex>)> = Vec::new();
+            let mut max_displacement: f64 = 0.0;
+            for cell in self.voronoi().cells.iter() {
+                let is_on_segment = self
+                    .segments
+                    .keys()
+                    .any(|(v1, v2)| *v1 == cell.site || *v2 == cell.site);
+                if !cell.is_bounded() || is_on_segment {
+                    continue;
+                }
+
+                if let Some((x, y)) = polygon_centroid(&cell.clip(&hull_coordinates)) {
+                    if self.walk_to(&Vertex::new(x, y)).is_none() {
+                        continue;
+                    }
+                    let displacement = (x - cell.site.x).hypot(y - cell.site.y);
+                    max_displacement = max_displacement.max(displacement);
+                    if displacement > 0.0 {
+                        moves.push((Rc::clone(&cell.site), Rc::new(Vertex::new(x, y))));
+                    }
+                }
+            }
+
+            for (from, to) in moves {
+                if !self.contains_vertex(&to) {
+                    self.move_vertex(&from, to);
+                }
+            }
+
+            if max_displacement <= tolerance {
+                return iteration + 1;
+            }
+        }
+        max_iterations
+    }
+
+    fn vertices_size(&self) -> usize {
+        let mut vertices_set: HashSet<Rc<Vertex>> = self.vertices.iter().cloned().collect();
+        for triangle in self.triangles.iter() {
+            vertices_set.insert(Rc::clone(&triangle.v1));
+            vertices_set.insert(Rc::clone(&triangle.v2));
+            vertices_set.insert(Rc::clone(&triangle.v3));
+        }
+
+        return vertices_set
+            .iter()
+            .filter(|vertex| !vertex.is_ghost)
+            .count();
+    }
+
+    fn triangles_size(&self) -> usize {
+        let mut triangles_set: HashSet<Rc<Triangle>> = self.triangles.iter().cloned().collect();
+
+        for triangle in self.conflict_map.keys() {
+            triangles_set.insert(Rc::clone(triangle));
+            triangles_set.insert(Rc::clone(triangle));
+            triangles_set.insert(Rc::clone(triangle));
+        }
+
+        return triangles_set
+            .iter()
+            .filter(|triangle| !triangle.is_ghost())
+            .count();
+    }
+
+    fn init(&mut self) {
+        let ghost_vertex = Rc::new(Vertex::new_ghost());
+
+        let mut v3 = self.vertices.pop().unwrap();
+        let mut v2 = self.vertices.pop().unwrap();
+        let mut v1 = self.vertices.pop().unwrap();
+        /* a repeated vertex is on a line with any other */
+        while v1 == v2 {
+            self.vertices.insert(0, v2);
+            v2 = self.vertices.pop().unwrap();
+        }
+
+        /* Loops until 3 non colinear vertices are found */
+        loop {
+            match orient_2d(&v1, &v2, &v3) {
                 Orientation::Counterclockwise => {
                     break;
                 }
@@ -355,7 +1181,7 @@ impl Triangulator {
             );
 
             /* If the cavity encircles the vertex, new cavities are to be analysed */
-            if outer_triangle.encircles(&vertex_to_insert) == Continence::Inside {
+            if self.is_in_conflict(&outer_triangle, &vertex_to_insert) {
                 /* disassembles */
                 self.remove_triangle(&outer_triangle);
                 let outer_v1 = &outer_triangle.v1;
@@ -394,7 +1220,7 @@ impl Triangulator {
 
         match self.vertices.iter().position(|vertex| {
             /* searchs for conflicting vertex */
-            triangle.encircles(vertex) == Continence::Inside
+            self.is_in_conflict(triangle, vertex)
         }) {
             Some(index) => {
                 let conflicting_vertex = self.vertices.remove(index);
@@ -445,167 +1271,622 @@ impl Triangulator {
     }
 
     /**
-     * Should be used against triangulations with no conflicts triangulations
+     * Moves a vertex keeping its index, marker and value, so exported indices are
+     * not shifted. Segments crossed by the new position are recovered.
      */
-    fn merge_triangles(&mut self, other: Self) {
-        let solid_triangle_vec: Vec<Rc<Triangle>> = other
-            .triangles
-            .iter()
-            .filter(|triangle| !triangle.is_ghost())
-            .cloned()
-            .collect();
-
-        for triangle in solid_triangle_vec {
-            self.triangles.insert(Rc::clone(&triangle));
+    fn move_vertex(&mut self, from: &Rc<Vertex>, to: Rc<Vertex>) {
+        self.delete_vertex(Rc::clone(from));
+        if let Some(marker) = self.vertex_markers.remove(from) {
+            self.vertex_markers.insert(Rc::clone(&to), marker);
         }
-
-        for ((v1, v2), val) in other.adjacency.iter() {
-            self.adjacency
-                .insert((Rc::clone(v1), Rc::clone(v2)), Rc::clone(val));
+        if let Some(value) = self.vertex_values.remove(from) {
+            self.vertex_values.insert(Rc::clone(&to), value);
+        }
+        if let Some(index) = self.vertex_indices.remove(from) {
+            self.vertex_indices.insert(Rc::clone(&to), index);
+        }
+        for indexed in self
+            .indexed_vertices
+            .iter_mut()
+            .filter(|indexed| *indexed == from)
+        {
+            *indexed = Rc::clone(&to);
         }
+        self.insert_vertex(to);
     }
-}
 
-#[cfg(test)]
-mod constructor {
-    use super::*;
+    /**
+     * Gives an index to a vertex arriving for the first time.
+     */
+    fn register_vertex(&mut self, vertex: &Rc<Vertex>) {
+        if !self.vertex_indices.contains_key(vertex) {
+            self.index_vertex(vertex);
+        }
+    }
 
-    #[test]
-    fn test_constructor() {
-        let vertex_indices = vec![0.0, 0.0, 2.0, 0.0, 1.0, 2.0];
-        let builder = Triangulator::from_coordinates(vertex_indices);
-        assert_eq!(builder.vertices.len(), 3);
+    /**
+     * Gives the next index to a vertex. An input vertex repeated at several
+     * indices is one vertex of the mesh, known by its first index.
+     */
+    fn index_vertex(&mut self, vertex: &Rc<Vertex>) {
+        self.vertex_indices
+            .entry(Rc::clone(vertex))
+            .or_insert(self.indexed_vertices.len());
+        self.indexed_vertices.push(Rc::clone(vertex));
     }
-}
 
-#[cfg(test)]
-mod init {
-    use super::*;
+    fn contains_vertex(&self, vertex: &Rc<Vertex>) -> bool {
+        self.adjacency.keys().any(|(v1, _)| v1 == vertex)
+    }
 
-    #[test]
-    fn test_init_single_triangle() {
-        let vertex_indices = vec![0.0, 0.0, 2.0, 0.0, 1.0, 2.0];
-        let mut builder = Triangulator::from_coordinates(vertex_indices);
-        builder.init();
-        assert_eq!(builder.vertices.len(), 0);
-        assert_eq!(builder.triangles.len(), 4);
+    /**
+     * Whether the vertex lies on the hull or on a hole boundary, where it has an
+     * edge towards the ghost vertex and cannot be deleted.
+     */
+    pub(crate) fn is_boundary_vertex(&self, vertex: &Rc<Vertex>) -> bool {
+        self.adjacency
+            .contains_key(&(Rc::clone(vertex), Rc::new(Vertex::new_ghost())))
     }
 
-    #[test]
-    fn test_init_triangle_with_conflict() {
-        let vertex_indices = vec![0.0, 0.0, 2.0, 0.0, 1.0, 2.0, 1.0, 1.0];
-        let mut builder = Triangulator::from_coordinates(vertex_indices);
-        builder.init();
-        assert_eq!(builder.vertices.len(), 0);
-        assert_eq!(builder.triangles.len() + builder.conflict_map.len(), 4);
+    /**
+     * Triangle the cavity of the vertex starts from: the solid triangle holding
+     * it, or a hull ghost triangle facing it if the vertex lies beyond the hull.
+     * None in a hole, in a carved area, or before triangulating.
+     */
+    fn conflicting_triangle(&self, vertex: &Vertex) -> Option<Rc<Triangle>> {
+        match self.walk_to(vertex) {
+            Some(triangle) => Some(triangle),
+            None => self
+                .triangles
+                .iter()
+                .find(|triangle| triangle.is_ghost() && self.is_in_conflict(triangle, vertex))
+                .cloned(),
+        }
     }
-}
 
-#[cfg(test)]
-mod triangulate {
-    use super::*;
+    pub(crate) fn can_insert_vertex(&self, vertex: &Vertex) -> bool {
+        self.conflicting_triangle(vertex).is_some()
+    }
 
-    #[test]
-    fn test_triangulate_4_vertices() {
-        let vertex_indices = vec![0.0, 0.0, 2.0, 0.0, 1.0, 2.0, 1.0, 1.0];
-        let mut builder = Triangulator::from_coordinates(vertex_indices);
-        builder.triangulate();
-        assert_eq!(builder.vertices.len(), 0);
-        assert_eq!(builder.triangles.len(), 6);
-        assert_eq!(builder.conflict_map.len(), 0);
+    pub(crate) fn is_triangulated(&self) -> bool {
+        !self.triangles.is_empty()
     }
-}
 
-#[cfg(test)]
-mod delete_vertex {
-    use super::*;
+    /**
+     * Whether the vertex is in conflict with the triangle. A ghost triangle stands
+     * for the half plane beyond its edge, which only holds for edges of the convex
+     * hull. The edges left by holes, or by an outer boundary where it is concave,
+     * are segments with vertices of the mesh beyond them, and are in conflict only
+     * with vertices lying on them.
+     */
+    fn is_in_conflict(&self, triangle: &Rc<Triangle>, vertex: &Vertex) -> bool {
+        if triangle.encircles(vertex) != Continence::Inside {
+            return false;
+        }
+        if !triangle.is_ghost() {
+            return true;
+        }
 
-    #[test]
-    fn test_remove_from_inside_triangle() {
-        let vertex_indices = vec![0.0, 0.0, 2.0, 0.0, 1.0, 2.0, 1.0, 1.0];
-        let mut triangulator = Triangulator::from_coordinates(vertex_indices);
-        triangulator.triangulate();
-        triangulator.delete_vertex(Rc::new(Vertex::new(1.0, 1.0)));
-        let solid_triangles: Vec<Rc<Triangle>> = triangulator
-            .triangles
-            .iter()
-            .filter(|triangle| !triangle.is_ghost())
-            .cloned()
-            .collect();
-        assert_eq!(solid_triangles.len(), 1);
+        let (v1, v2) = triangle
+            .edges()
+            .into_iter()
+            .find(|(v1, v2)| !v1.is_ghost && !v2.is_ghost)
+            .unwrap();
+        orient_2d(&v1, &v2, vertex) == Orientation::Colinear
+            || !self.is_segment(&v1, &v2)
+            || !self.adjacency.keys().any(|(other, _)| {
+                !other.is_ghost
+                    && **other != *vertex
+                    && orient_2d(&v1, &v2, other) == Orientation::Counterclockwise
+            })
     }
 
-    #[test]
-    fn test_remove_from_inside_hexagon() {
-        let vertex_indices = vec![
-            1.0, 0.0, 2.0, 0.0, 3.0, 1.0, 2.0, 2.0, 1.0, 2.0, 0.0, 1.0, 1.2, 1.0, 2.0, 1.0,
-        ];
-        /*
-           (1.0, 0.0)
-           (2.0, 0.0)
-           (3.0, 1.0)
-           (2.0, 2.0)
-           (1.0, 2.0)
-           (0.0, 1.0)
-           (1.2, 1.0)
-           (2.0, 1.0)
-        */
-        let mut triangulator = Triangulator::from_coordinates(vertex_indices);
-        triangulator.triangulate();
-        let solid_triangles: Vec<Rc<Triangle>> = triangulator
-            .triangles
-            .iter()
-            .filter(|triangle| !triangle.is_ghost())
-            .cloned()
-            .collect();
-        assert_eq!(solid_triangles.len(), 8);
+    fn is_segment(&self, v1: &Rc<Vertex>, v2: &Rc<Vertex>) -> bool {
+        self.segment_marker(v1, v2).is_some()
+    }
 
-        triangulator.delete_vertex(Rc::new(Vertex::new(2.0, 1.0)));
-        let solid_triangles: Vec<Rc<Triangle>> = triangulator
-            .triangles
-            .iter()
-            .filter(|triangle| !triangle.is_ghost())
-            .cloned()
-            .collect();
-        assert_eq!(solid_triangles.len(), 6);
+    fn segment_marker(&self, v1: &Rc<Vertex>, v2: &Rc<Vertex>) -> Option<i32> {
+        match self.segments.get(&(Rc::clone(v1), Rc::clone(v2))) {
+            Some(marker) => Some(*marker),
+            None => self.segments.get(&(Rc::clone(v2), Rc::clone(v1))).cloned(),
+        }
     }
 
-    #[test]
-    #[should_panic]
-    fn test_panics_at_boundary() {
-        let vertex_indices = vec![0.0, 0.0, 2.0, 0.0, 1.0, 2.0, 1.0, 1.0];
-        let mut triangulator = Triangulator::from_coordinates(vertex_indices);
-        triangulator.triangulate();
-        triangulator.delete_vertex(Rc::new(Vertex::new(2.0, 0.0)));
+    fn mark_unmarked_vertex(&mut self, vertex: &Rc<Vertex>, marker: i32) {
+        let current_marker = self.vertex_markers.entry(Rc::clone(vertex)).or_insert(0);
+        if *current_marker == 0 {
+            *current_marker = marker;
+        }
     }
-}
 
-#[cfg(test)]
-mod insert_vertex {
-    use super::*;
+    fn recover_segments(&mut self) {
+        loop {
+            let missing_segment = self
+                .segments
+                .keys()
+                .find(|(v1, v2)| {
+                    let has_edge = self.adjacency.contains_key(&(Rc::clone(v1), Rc::clone(v2)));
+                    let has_twin = self.adjacency.contains_key(&(Rc::clone(v2), Rc::clone(v1)));
+                    !has_edge && !has_twin
+                })
+                .cloned();
+
+            match missing_segment {
+                Some((v1, v2)) => self.split_segment(&v1, &v2),
+                None => break,
+            }
+        }
+    }
 
-    #[test]
-    fn test_insert_outside() {
-        let vertex_indices = vec![0.0, 0.0, 2.0, 0.0, 1.0, 2.0];
-        let mut triangulator = Triangulator::from_coordinates(vertex_indices);
-        triangulator.triangulate();
+    /**
+     * Splits the segment at a vertex already lying on it or, if there is none, at
+     * its midpoint. Splitting at existing vertices avoids endless bisection towards
+     * a vertex the midpoints never reach.
+     */
+    fn split_segment(&mut self, v1: &Rc<Vertex>, v2: &Rc<Vertex>) {
+        let marker = self
+            .segments
+            .remove(&(Rc::clone(v1), Rc::clone(v2)))
+            .unwrap();
+
+        let on_segment = self
+            .adjacency
+            .keys()
+            .map(|(vertex, _)| vertex)
+            .find(|vertex| {
+                !vertex.is_ghost
+                    && orient_2d(v1, v2, vertex) == Orientation::Colinear
+                    && (vertex.x - v1.x) * (vertex.x - v2.x) + (vertex.y - v1.y) * (vertex.y - v2.y)
+                        < 0.0
+            })
+            .cloned();
 
-        let new_vertex = Rc::new(Vertex::new(2.0, 2.0));
-        triangulator.insert_vertex(new_vertex);
-        let solid_triangles: Vec<Rc<Triangle>> = triangulator
-            .triangles
+        let splitting_vertex = match on_segment {
+            Some(vertex) => vertex,
+            None => {
+                let midpoint = Rc::new(Vertex::mean(&[v1, v2]));
+                self.insert_unconstrained_vertex(Rc::clone(&midpoint));
+                midpoint
+            }
+        };
+
+        self.mark_unmarked_vertex(&splitting_vertex, marker);
+        self.segments
+            .insert((Rc::clone(v1), Rc::clone(&splitting_vertex)), marker);
+        self.segments
+            .insert((Rc::clone(&splitting_vertex), Rc::clone(v2)), marker);
+    }
+
+    /**
+     * Finds the vertex following v1 along the subsegments recovered from segment (v1, v2).
+     */
+    fn subsegment_from(&self, v1: &Rc<Vertex>, v2: &Rc<Vertex>) -> Rc<Vertex> {
+        if self.is_segment(v1, v2) {
+            return Rc::clone(v2);
+        }
+
+        self.segments
+            .keys()
+            .filter_map(|(s1, s2)| {
+                if s1 == v1 {
+                    Some(s2)
+                } else if s2 == v1 {
+                    Some(s1)
+                } else {
+                    None
+                }
+            })
+            .find(|vertex| {
+                orient_2d(v1, v2, vertex) == Orientation::Colinear
+                    && (vertex.x - v1.x) * (v2.x - v1.x) + (vertex.y - v1.y) * (v2.y - v1.y) > 0.0
+            })
+            .cloned()
+            .unwrap()
+    }
+
+    /**
+     * Walks from triangle to triangle towards the point, across the edges leaving
+     * it on their clockwise side, until reaching a solid triangle containing it.
+     */
+    fn walk_to(&self, point: &Vertex) -> Option<Rc<Triangle>> {
+        let mut triangle = Rc::clone(
+            self.triangles
+                .iter()
+                .find(|triangle| !triangle.is_ghost())?,
+        );
+
+        for _ in 0..self.triangles.len() {
+            let exit_edge = triangle
+                .edges()
+                .into_iter()
+                .find(|(v1, v2)| orient_2d(v1, v2, point) == Orientation::Clockwise);
+            match exit_edge {
+                None => return Some(triangle),
+                Some((v1, v2)) => match self.adjacency.get(&(v2, v1)) {
+                    Some(neighbor) if !neighbor.is_ghost() => triangle = Rc::clone(neighbor),
+                    _ => break,
+                },
+            }
+        }
+
+        /* the walk left the domain, which is not convex around holes */
+        self.find_containing_triangle(point)
+    }
+
+    /**
+     * Expands through the edges from the vertex nearest to the point, visiting
+     * vertices by increasing distance while `accept` holds for the number of
+     * vertices already found and the next distance. Delaunay edges make the
+     * expansion exact, and descending greedily along them reaches the nearest
+     * vertex. Edges removed with holes may hide vertices across them.
+     */
+    fn nearest_vertices<F>(&self, x: f64, y: f64, accept: F) -> Vec<(usize, f64)>
+    where
+        F: Fn(usize, f64) -> bool,
+    {
+        let point = Vertex::new(x, y);
+        let distance = |vertex: &Vertex| (vertex.x - x).hypot(vertex.y - y);
+        let start_triangle = match self.walk_to(&point) {
+            Some(triangle) => triangle,
+            None => match self.nearest_boundary_edge(&point) {
+                Some(Location::Outside(v1, v2)) => Rc::clone(&self.adjacency[&(v2, v1)]),
+                _ => return Vec::new(),
+            },
+        };
+
+        let mut nearest = Rc::clone(&start_triangle.v1);
+        let mut nearest_triangle = Rc::clone(&start_triangle);
+        for vertex in [&start_triangle.v2, &start_triangle.v3].iter() {
+            if distance(vertex) < distance(&nearest) {
+                nearest = Rc::clone(vertex);
+            }
+        }
+        while let Some((closer, triangle)) = self
+            .vertex_neighbors(&nearest, &nearest_triangle)
+            .into_iter()
+            .find(|(neighbor, _)| distance(neighbor) < distance(&nearest))
+        {
+            nearest = closer;
+            nearest_triangle = triangle;
+        }
+
+        /* non-negative floats are ordered as their bits */
+        let mut pending: BinaryHeap<(Reverse<u64>, Rc<Vertex>)> = BinaryHeap::new();
+        let mut reached: HashMap<Rc<Vertex>, Rc<Triangle>> = HashMap::new();
+        pending.push((Reverse(distance(&nearest).to_bits()), Rc::clone(&nearest)));
+        reached.insert(nearest, nearest_triangle);
+
+        let mut found: Vec<(usize, f64)> = Vec::new();
+        while let Some((Reverse(bits), vertex)) = pending.pop() {
+            let vertex_distance = f64::from_bits(bits);
+            if !accept(found.len(), vertex_distance) {
+                break;
+            }
+            found.push((self.vertex_indices[&vertex], vertex_distance));
+
+            for (neighbor, triangle) in self.vertex_neighbors(&vertex, &reached[&vertex]) {
+                if let Entry::Vacant(entry) = reached.entry(Rc::clone(&neighbor)) {
+                    entry.insert(triangle);
+                    pending.push((Reverse(distance(&neighbor).to_bits()), neighbor));
+                }
+            }
+        }
+        found
+    }
+
+    /**
+     * Solid vertices sharing an edge with the vertex, each with a triangle holding
+     * it, found by rotating from a triangle around the vertex.
+     */
+    fn vertex_neighbors(
+        &self,
+        vertex: &Rc<Vertex>,
+        first_triangle: &Rc<Triangle>,
+    ) -> Vec<(Rc<Vertex>, Rc<Triangle>)> {
+        let mut neighbors: Vec<(Rc<Vertex>, Rc<Triangle>)> = Vec::new();
+        let mut triangle = Rc::clone(first_triangle);
+        for _ in 0..self.triangles.len() {
+            let next_vertex = if triangle.v1 == *vertex {
+                Rc::clone(&triangle.v3)
+            } else if triangle.v2 == *vertex {
+                Rc::clone(&triangle.v1)
+            } else {
+                Rc::clone(&triangle.v2)
+            };
+            if !next_vertex.is_ghost {
+                neighbors.push((Rc::clone(&next_vertex), Rc::clone(&triangle)));
+            }
+
+            triangle = match self.adjacency.get(&(Rc::clone(vertex), next_vertex)) {
+                Some(next) => Rc::clone(next),
+                None => break,
+            };
+            if triangle == *first_triangle {
+                break;
+            }
+        }
+        neighbors
+    }
+
+    fn location_in(triangle: &Rc<Triangle>, point: &Vertex) -> Location {
+        for vertex in [&triangle.v1, &triangle.v2, &triangle.v3].iter() {
+            if ***vertex == *point {
+                return Location::OnVertex(Rc::clone(vertex));
+            }
+        }
+        for (v1, v2) in triangle.edges() {
+            if orient_2d(&v1, &v2, point) == Orientation::Colinear {
+                return Location::OnEdge(Rc::clone(triangle), v1, v2);
+            }
+        }
+        Location::Inside(Rc::clone(triangle))
+    }
+
+    fn nearest_boundary_edge(&self, point: &Vertex) -> Option<Location> {
+        self.triangles
             .iter()
+            .filter(|triangle| triangle.is_ghost())
+            .filter_map(|ghost| {
+                ghost
+                    .edges()
+                    .into_iter()
+                    .find(|(v1, v2)| !v1.is_ghost && !v2.is_ghost)
+            })
+            .map(|(v1, v2)| (segment_distance(&v1, &v2, point), v1, v2))
+            .min_by(|(d1, _, _), (d2, _, _)| d1.partial_cmp(d2).unwrap())
+            .map(|(_, v1, v2)| Location::Outside(v1, v2))
+    }
+
+    fn find_containing_triangle(&self, vertex: &Vertex) -> Option<Rc<Triangle>> {
+        self.triangles
+            .iter()
+            .find(|triangle| triangle.contains(vertex) != Continence::Outside)
+            .cloned()
+    }
+
+    /**
+     * Collects the solid triangles reachable from the seeds without crossing
+     * constrained segments.
+     */
+    fn flood_fill(&self, seed_triangles: Vec<Rc<Triangle>>) -> HashSet<Rc<Triangle>> {
+        let mut reached: HashSet<Rc<Triangle>> = HashSet::new();
+        let mut pending: Vec<Rc<Triangle>> = seed_triangles
+            .into_iter()
             .filter(|triangle| !triangle.is_ghost())
+            .collect();
+
+        while let Some(triangle) = pending.pop() {
+            if !reached.insert(Rc::clone(&triangle)) {
+                continue;
+            }
+
+            for (v1, v2) in triangle.edges() {
+                if self.is_segment(&v1, &v2) {
+                    continue;
+                }
+                if let Some(neighbor) = self.adjacency.get(&(v2, v1)) {
+                    if !neighbor.is_ghost() && !reached.contains(neighbor) {
+                        pending.push(Rc::clone(neighbor));
+                    }
+                }
+            }
+        }
+
+        reached
+    }
+
+    fn region_attributes(&self) -> HashMap<Rc<Triangle>, f64> {
+        let mut attributes: HashMap<Rc<Triangle>, f64> = HashMap::new();
+        for (seed, attribute) in self.regions.iter() {
+            if let Some(seed_triangle) = self.find_containing_triangle(seed) {
+                for triangle in self.flood_fill(vec![seed_triangle]) {
+                    attributes.insert(triangle, *attribute);
+                }
+            }
+        }
+        attributes
+    }
+
+    /**
+     * Replaces the region flood filled from the seeds with ghost triangles facing
+     * the remaining mesh.
+     */
+    fn remove_enclosed_triangles(&mut self, seed_triangles: Vec<Rc<Triangle>>) {
+        let enclosed = self.flood_fill(seed_triangles);
+
+        /* edges facing the remaining solid triangles become ghost edges, and
+        ghost triangles facing the removed ones go with them */
+        let mut boundary_edges: Vec<(Rc<Vertex>, Rc<Vertex>)> = Vec::new();
+        let mut outer_ghosts: HashSet<Rc<Triangle>> = HashSet::new();
+        for triangle in enclosed.iter() {
+            for (v1, v2) in triangle.edges() {
+                if let Some(neighbor) = self.adjacency.get(&(Rc::clone(&v2), Rc::clone(&v1))) {
+                    if neighbor.is_ghost() {
+                        outer_ghosts.insert(Rc::clone(neighbor));
+                    } else if !enclosed.contains(neighbor) {
+                        boundary_edges.push((v1, v2));
+                    }
+                }
+            }
+        }
+
+        for triangle in enclosed.iter().chain(outer_ghosts.iter()) {
+            self.remove_triangle(triangle);
+        }
+
+        let ghost_vertex = Rc::new(Vertex::new_ghost());
+        for (v1, v2) in boundary_edges.iter() {
+            let ghost_triangle = Rc::new(Triangle::new(v1, v2, &ghost_vertex));
+            self.include_triangle(&ghost_triangle);
+        }
+    }
+
+    /**
+     * Should be used against triangulations with no conflicts triangulations.
+     * The other triangulation covers the convex hull of its vertices, so only the
+     * triangles enclosed by the cavity edges are merged: a cavity whose boundary
+     * is not convex would otherwise overlap the surrounding triangles.
+     */
+    fn merge_triangles(&mut self, other: Self, cavity_edges: &HashSet<(Rc<Vertex>, Rc<Vertex>)>) {
+        let mut enclosed: HashSet<Rc<Triangle>> = HashSet::new();
+        let mut pending: Vec<Rc<Triangle>> = cavity_edges
+            .iter()
+            .filter_map(|edge| other.adjacency.get(edge))
             .cloned()
             .collect();
-        assert_eq!(solid_triangles.len(), 2);
+
+        while let Some(triangle) = pending.pop() {
+            if triangle.is_ghost() || !enclosed.insert(Rc::clone(&triangle)) {
+                continue;
+            }
+
+            for (v1, v2) in triangle.edges() {
+                if cavity_edges.contains(&(Rc::clone(&v1), Rc::clone(&v2))) {
+                    continue;
+                }
+                if let Some(neighbor) = other.adjacency.get(&(v2, v1)) {
+                    pending.push(Rc::clone(neighbor));
+                }
+            }
+        }
+
+        for triangle in enclosed.iter() {
+            self.include_inner_adjacency(triangle);
+            self.triangles.insert(Rc::clone(triangle));
+        }
     }
+}
+
+/* points scattered over [0, 10] x [0, 10], in no particular order */
+#[cfg(test)]
+fn scattered_coordinates(size: usize) -> Vec<f64> {
+    (0..size)
+        .flat_map(|index| {
+            let index = index as f64;
+            vec![
+                5.0 + 5.0 * (index * 1.7).sin(),
+                5.0 + 5.0 * (index * 2.3).cos(),
+            ]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod constructor {
+    use super::*;
 
     #[test]
-    fn test_inside_triangle() {
+    fn test_constructor() {
+        let vertex_indices = vec![0.0, 0.0, 2.0, 0.0, 1.0, 2.0];
+        let builder = Triangulator::from_coordinates(vertex_indices);
+        assert_eq!(builder.vertices.len(), 3);
+    }
+}
+
+#[cfg(test)]
+mod init {
+    use super::*;
+
+    #[test]
+    fn test_init_single_triangle() {
         let vertex_indices = vec![0.0, 0.0, 2.0, 0.0, 1.0, 2.0];
+        let mut builder = Triangulator::from_coordinates(vertex_indices);
+        builder.init();
+        assert_eq!(builder.vertices.len(), 0);
+        assert_eq!(builder.triangles.len(), 4);
+    }
+
+    #[test]
+    fn test_init_triangle_with_conflict() {
+        let vertex_indices = vec![0.0, 0.0, 2.0, 0.0, 1.0, 2.0, 1.0, 1.0];
+        let mut builder = Triangulator::from_coordinates(vertex_indices);
+        builder.init();
+        assert_eq!(builder.vertices.len(), 0);
+        assert_eq!(builder.triangles.len() + builder.conflict_map.len(), 4);
+    }
+
+    #[test]
+    fn test_init_skips_repeated_vertex() {
+        let vertex_indices = vec![0.0, 0.0, 1.0, 2.0, 1.0, 2.0, 2.0, 0.0];
+        let mut builder = Triangulator::from_coordinates(vertex_indices);
+        assert!(builder.spans_triangle());
+        builder.triangulate();
+        assert_eq!(builder.triangles_size(), 1);
+    }
+
+    #[test]
+    fn test_spans_triangle() {
+        let vertex_indices = vec![0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 2.0, 2.0];
+        assert!(!Triangulator::from_coordinates(vertex_indices).spans_triangle());
+        let vertex_indices = vec![0.0, 0.0, 1.0, 1.0, 1.0, 1.0];
+        assert!(!Triangulator::from_coordinates(vertex_indices).spans_triangle());
+        assert!(!Triangulator::new().spans_triangle());
+    }
+}
+
+#[cfg(test)]
+mod triangulate {
+    use super::*;
+
+    #[test]
+    fn test_triangulate_4_vertices() {
+        let vertex_indices = vec![0.0, 0.0, 2.0, 0.0, 1.0, 2.0, 1.0, 1.0];
+        let mut builder = Triangulator::from_coordinates(vertex_indices);
+        builder.triangulate();
+        assert_eq!(builder.vertices.len(), 0);
+        assert_eq!(builder.triangles.len(), 6);
+        assert_eq!(builder.conflict_map.len(), 0);
+    }
+
+    #[test]
+    fn test_inserts_vertices_left_without_conflict() {
+        /* hexagon in an order that leaves (1.0, 2.0) out of every new triangle */
+        let vertex_indices = vec![
+            2.0, 2.0, 0.0, 1.0, 3.0, 1.0, 1.0, 2.0, 1.2, 1.0, 2.0, 0.0, 2.0, 1.0, 1.0, 0.0,
+        ];
+        let mut builder = Triangulator::from_coordinates(vertex_indices);
+        builder.triangulate();
+        assert_eq!(builder.vertices.len(), 0);
+        assert_eq!(builder.triangles_size(), 8);
+    }
+
+    #[test]
+    fn test_triangulate_grid() {
+        /* every cell of the grid has four cocircular corners */
+        let mut vertex_indices = Vec::new();
+        for x in 0..5 {
+            for y in 0..5 {
+                vertex_indices.push(x as f64 / 10.0);
+                vertex_indices.push(y as f64 / 10.0);
+            }
+        }
+        for _ in 0..10 {
+            let mut builder = Triangulator::from_coordinates(vertex_indices.clone());
+            builder.triangulate();
+            let area: f64 = builder
+                .triangles
+                .iter()
+                .map(|triangle| triangle.area())
+                .sum();
+            assert!((area - 0.16).abs() < 1e-12);
+            assert_eq!(
+                builder.triangles.iter().filter(|t| !t.is_ghost()).count(),
+                32
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod delete_vertex {
+    use super::*;
+
+    #[test]
+    fn test_remove_from_inside_triangle() {
+        let vertex_indices = vec![0.0, 0.0, 2.0, 0.0, 1.0, 2.0, 1.0, 1.0];
         let mut triangulator = Triangulator::from_coordinates(vertex_indices);
         triangulator.triangulate();
+        triangulator.delete_vertex(Rc::new(Vertex::new(1.0, 1.0)));
         let solid_triangles: Vec<Rc<Triangle>> = triangulator
             .triangles
             .iter()
@@ -613,22 +1894,12 @@ mod insert_vertex {
             .cloned()
             .collect();
         assert_eq!(solid_triangles.len(), 1);
-
-        let new_vertex = Rc::new(Vertex::new(1.0, 1.0));
-        triangulator.insert_vertex(new_vertex);
-        let solid_triangles: Vec<Rc<Triangle>> = triangulator
-            .triangles
-            .iter()
-            .filter(|triangle| !triangle.is_ghost())
-            .cloned()
-            .collect();
-        assert_eq!(solid_triangles.len(), 3);
     }
 
     #[test]
-    fn test_inside_hexagon() {
+    fn test_remove_from_inside_hexagon() {
         let vertex_indices = vec![
-            1.0, 0.0, 2.0, 0.0, 3.0, 1.0, 2.0, 2.0, 1.0, 2.0, 0.0, 1.0, 1.2, 1.0,
+            1.0, 0.0, 2.0, 0.0, 3.0, 1.0, 2.0, 2.0, 1.0, 2.0, 0.0, 1.0, 1.2, 1.0, 2.0, 1.0,
         ];
         /*
            (1.0, 0.0)
@@ -648,116 +1919,1811 @@ mod insert_vertex {
             .filter(|triangle| !triangle.is_ghost())
             .cloned()
             .collect();
-        assert_eq!(solid_triangles.len(), 6);
+        assert_eq!(solid_triangles.len(), 8);
 
-        let new_vertex = Rc::new(Vertex::new(2.0, 1.0));
-        triangulator.insert_vertex(new_vertex);
+        triangulator.delete_vertex(Rc::new(Vertex::new(2.0, 1.0)));
         let solid_triangles: Vec<Rc<Triangle>> = triangulator
             .triangles
             .iter()
             .filter(|triangle| !triangle.is_ghost())
             .cloned()
             .collect();
-        assert_eq!(solid_triangles.len(), 8);
+        assert_eq!(solid_triangles.len(), 6);
     }
-}
-
-#[cfg(test)]
-mod insert_hole {
-    use super::*;
 
     #[test]
-    fn test_triangle_hole_inside_triangle() {
-        let mut triangulator = Triangulator::from_coordinates(vec![0.0, 0.0, 10.0, 0.0, 5.0, 10.0]);
+    fn test_remove_with_concave_cavity() {
+        let vertex_indices = vec![
+            0.0, 0.0, 10.0, 0.0, 10.0, 10.0, 0.0, 10.0, 5.0, 5.0, 5.0, 5.4, 3.0, 6.0, 7.0, 6.0,
+        ];
         /*
-            ( 0.0,  0.0)
-            (10.0,  0.0)
-            ( 5.0, 10.0)
+           (5.0, 5.4) makes the boundary of the cavity of (5.0, 5.0) concave
         */
-        let hole_path = Vertex::from_coordinates(vec![5.0, 2.0, 4.0, 3.0, 3.0, 3.0]);
-        /*
-           (5.0, 2.0)
-           (4.0, 3.0)
-           (3.0, 3.0)
+        let mut triangulator = Triangulator::from_coordinates(vertex_indices);
+        triangulator.triangulate();
+        triangulator.delete_vertex(Rc::new(Vertex::new(5.0, 5.0)));
+
+        let area: f64 = triangulator
+            .triangles
+            .iter()
+            .map(|triangle| triangle.area())
+            .sum();
+        assert!((area - 100.0).abs() < 1e-9);
+        assert_eq!(triangulator.triangles_size(), 2 * 7 - 4 - 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_panics_at_boundary() {
+        let vertex_indices = vec![0.0, 0.0, 2.0, 0.0, 1.0, 2.0, 1.0, 1.0];
+        let mut triangulator = Triangulator::from_coordinates(vertex_indices);
+        triangulator.triangulate();
+        triangulator.delete_vertex(Rc::new(Vertex::new(2.0, 0.0)));
+    }
+}
+
+#[cfg(test)]
+mod insert_vertex {
+    use super::*;
+
+    /* solid area of the mesh, asserting each triangle is counterclockwise */
+    fn checked_area(triangulator: &Triangulator) -> f64 {
+        triangulator
+            .triangles
+            .iter()
+            .filter(|triangle| !triangle.is_ghost())
+            .map(|triangle| {
+                assert_eq!(
+                    orient_2d(&triangle.v1, &triangle.v2, &triangle.v3),
+                    Orientation::Counterclockwise
+                );
+                triangle.area()
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_around_hole() {
+        let mut triangulator =
+            Triangulator::from_coordinates(vec![0.0, 0.0, 10.0, 0.0, 10.0, 10.0, 0.0, 10.0]);
+        triangulator.triangulate();
+        triangulator.insert_hole(Vertex::from_coordinates(vec![
+            4.0, 4.0, 6.0, 4.0, 6.0, 6.0, 4.0, 6.0,
+        ]));
+
+        /* hole edges face most of these points, yet none of them is in conflict */
+        for point in scattered_coordinates(60).chunks(2) {
+            let in_hole = (4.0..=6.0).contains(&point[0]) && (4.0..=6.0).contains(&point[1]);
+            if !in_hole {
+                triangulator.insert_vertex(Rc::new(Vertex::new(point[0], point[1])));
+                assert!((checked_area(&triangulator) - 96.0).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_beyond_hull_with_hole() {
+        let mut triangulator =
+            Triangulator::from_coordinates(vec![0.0, 0.0, 10.0, 0.0, 10.0, 10.0, 0.0, 10.0]);
+        triangulator.triangulate();
+        triangulator.insert_hole(Vertex::from_coordinates(vec![
+            4.0, 4.0, 6.0, 4.0, 6.0, 6.0, 4.0, 6.0,
+        ]));
+
+        triangulator.insert_vertex(Rc::new(Vertex::new(5.0, -2.0)));
+        assert!((checked_area(&triangulator) - 106.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_insert_outside() {
+        let vertex_indices = vec![0.0, 0.0, 2.0, 0.0, 1.0, 2.0];
+        let mut triangulator = Triangulator::from_coordinates(vertex_indices);
+        triangulator.triangulate();
+
+        let new_vertex = Rc::new(Vertex::new(2.0, 2.0));
+        triangulator.insert_vertex(new_vertex);
+        let solid_triangles: Vec<Rc<Triangle>> = triangulator
+            .triangles
+            .iter()
+            .filter(|triangle| !triangle.is_ghost())
+            .cloned()
+            .collect();
+        assert_eq!(solid_triangles.len(), 2);
+    }
+
+    #[test]
+    fn test_inside_triangle() {
+        let vertex_indices = vec![0.0, 0.0, 2.0, 0.0, 1.0, 2.0];
+        let mut triangulator = Triangulator::from_coordinates(vertex_indices);
+        triangulator.triangulate();
+        let solid_triangles: Vec<Rc<Triangle>> = triangulator
+            .triangles
+            .iter()
+            .filter(|triangle| !triangle.is_ghost())
+            .cloned()
+            .collect();
+        assert_eq!(solid_triangles.len(), 1);
+
+        let new_vertex = Rc::new(Vertex::new(1.0, 1.0));
+        triangulator.insert_vertex(new_vertex);
+        let solid_triangles: Vec<Rc<Triangle>> = triangulator
+            .triangles
+            .iter()
+            .filter(|triangle| !triangle.is_ghost())
+            .cloned()
+            .collect();
+        assert_eq!(solid_triangles.len(), 3);
+    }
+
+    #[test]
+    fn test_inside_hexagon() {
+        let vertex_indices = vec![
+            1.0, 0.0, 2.0, 0.0, 3.0, 1.0, 2.0, 2.0, 1.0, 2.0, 0.0, 1.0, 1.2, 1.0,
+        ];
+        /*
+           (1.0, 0.0)
+           (2.0, 0.0)
+           (3.0, 1.0)
+           (2.0, 2.0)
+           (1.0, 2.0)
+           (0.0, 1.0)
+           (1.2, 1.0)
+           (2.0, 1.0)
+        */
+        let mut triangulator = Triangulator::from_coordinates(vertex_indices);
+        triangulator.triangulate();
+        let solid_triangles: Vec<Rc<Triangle>> = triangulator
+            .triangles
+            .iter()
+            .filter(|triangle| !triangle.is_ghost())
+            .cloned()
+            .collect();
+        assert_eq!(solid_triangles.len(), 6);
+
+        let new_vertex = Rc::new(Vertex::new(2.0, 1.0));
+        triangulator.insert_vertex(new_vertex);
+        let solid_triangles: Vec<Rc<Triangle>> = triangulator
+            .triangles
+            .iter()
+            .filter(|triangle| !triangle.is_ghost())
+            .cloned()
+            .collect();
+        assert_eq!(solid_triangles.len(), 8);
+    }
+}
+
+#[cfg(test)]
+mod insert_hole {
+    use super::*;
+
+    #[test]
+    fn test_triangle_hole_inside_triangle() {
+        let mut triangulator = Triangulator::from_coordinates(vec![0.0, 0.0, 10.0, 0.0, 5.0, 10.0]);
+        /*
+            ( 0.0,  0.0)
+            (10.0,  0.0)
+            ( 5.0, 10.0)
+        */
+        let hole_path = Vertex::from_coordinates(vec![5.0, 2.0, 4.0, 3.0, 3.0, 3.0]);
+        /*
+           (5.0, 2.0)
+           (4.0, 3.0)
+           (3.0, 3.0)
+        */
+
+        triangulator.triangulate();
+        triangulator.insert_hole(hole_path);
+
+        assert_eq!(triangulator.vertices_size(), 6);
+        assert_eq!(triangulator.triangles_size(), 6);
+    }
+
+    #[test]
+    fn test_removes_triangles_enclosed_by_hole() {
+        let mut triangulator = Triangulator::from_coordinates(vec![
+            0.0, 0.0, 10.0, 0.0, 10.0, 10.0, 0.0, 10.0, 5.0, 5.0, 4.5, 5.5,
+        ]);
+        /*
+            ( 0.0,  0.0)
+            (10.0,  0.0)
+            (10.0, 10.0)
+            ( 0.0, 10.0)
+            ( 5.0,  5.0) inside the hole
+            ( 4.5,  5.5) inside the hole
+        */
+        let hole_path = Vertex::from_coordinates(vec![3.0, 3.0, 7.0, 3.0, 7.0, 7.0, 3.0, 7.0]);
+
+        triangulator.triangulate();
+        triangulator.insert_hole(hole_path);
+
+        let solid_triangles: Vec<Rc<Triangle>> = triangulator
+            .triangles
+            .iter()
+            .filter(|triangle| !triangle.is_ghost())
+            .cloned()
+            .collect();
+        let area: f64 = solid_triangles.iter().map(|triangle| triangle.area()).sum();
+        assert!((area - 84.0).abs() < 1e-9);
+
+        let inner_vertex = Rc::new(Vertex::new(5.0, 5.0));
+        assert!(!triangulator.contains_vertex(&inner_vertex));
+    }
+
+    #[test]
+    fn test_clockwise_hole_boundary() {
+        let mut triangulator = Triangulator::from_coordinates(vec![
+            0.0, 0.0, 10.0, 0.0, 10.0, 10.0, 0.0, 10.0, 5.0, 5.0,
+        ]);
+        let hole_path = Vertex::from_coordinates(vec![3.0, 3.0, 3.0, 7.0, 7.0, 7.0, 7.0, 3.0]);
+
+        triangulator.triangulate();
+        triangulator.insert_hole(hole_path);
+
+        let area: f64 = triangulator
+            .triangles
+            .iter()
+            .map(|triangle| triangle.area())
+            .sum();
+        assert!((area - 84.0).abs() < 1e-9);
+    }
+}
+
+#[cfg(test)]
+mod insert_boundary {
+    use super::*;
+
+    #[test]
+    fn test_concave_boundary_with_hole() {
+        let outer = Vertex::from_coordinates(vec![
+            0.0, 0.0, 6.0, 0.0, 6.0, 2.0, 2.0, 2.0, 2.0, 6.0, 0.0, 6.0,
+        ]);
+        let hole = Vertex::from_coordinates(vec![0.5, 0.5, 0.5, 1.5, 1.5, 1.5, 1.5, 0.5]);
+        let mut vertices = outer.clone();
+        vertices.extend(hole.iter().cloned());
+        vertices.push(Rc::new(Vertex::new(4.1, 3.7)));
+        let mut triangulator = Triangulator::from_vertices(vertices);
+        triangulator.triangulate();
+        triangulator.insert_boundary(&[outer, hole]);
+
+        let area: f64 = triangulator
+            .triangles
+            .iter()
+            .map(|triangle| triangle.area())
+            .sum();
+        assert!((area - 19.0).abs() < 1e-9);
+        assert!(!triangulator.contains_vertex(&Rc::new(Vertex::new(4.1, 3.7))));
+
+        /* the hull spans the notch of the L-shaped outer ring */
+        let hull = triangulator.convex_hull();
+        assert!((hull.area() - 28.0).abs() < 1e-9);
+    }
+}
+
+#[cfg(test)]
+mod insert_segment {
+    use super::*;
+
+    #[test]
+    fn test_recovers_missing_segment() {
+        let mut triangulator =
+            Triangulator::from_coordinates(vec![0.0, 0.0, 4.0, 0.0, 4.0, 1.0, 0.0, 1.0, 2.0, 0.5]);
+        triangulator.triangulate();
+
+        let v1 = Rc::new(Vertex::new(0.0, 0.0));
+        let v2 = Rc::new(Vertex::new(4.0, 1.0));
+        triangulator.insert_segment(Rc::clone(&v1), Rc::clone(&v2));
+
+        /* (2.0, 0.5) lies on the segment, so it splits the segment */
+        let splitting_vertex = Rc::new(Vertex::new(2.0, 0.5));
+        assert!(triangulator.is_segment(&v1, &splitting_vertex));
+        assert!(triangulator.is_segment(&splitting_vertex, &v2));
+        for (s1, s2) in triangulator.segments.keys() {
+            assert!(
+                triangulator
+                    .adjacency
+                    .contains_key(&(Rc::clone(s1), Rc::clone(s2)))
+                    || triangulator
+                        .adjacency
+                        .contains_key(&(Rc::clone(s2), Rc::clone(s1)))
+            );
+        }
+    }
+
+    #[test]
+    fn test_splits_segment_at_midpoints() {
+        let mut triangulator = Triangulator::from_coordinates(vec![
+            0.0, 0.0, 10.0, 0.0, 10.0, 2.0, 0.0, 2.0, 5.0, 0.8, 5.0, 1.2,
+        ]);
+        triangulator.triangulate();
+
+        let v1 = Rc::new(Vertex::new(0.0, 1.0));
+        let v2 = Rc::new(Vertex::new(10.0, 1.0));
+        triangulator.insert_segment(Rc::clone(&v1), Rc::clone(&v2));
+
+        assert!(triangulator.segments.len() > 1);
+        assert!(!triangulator.is_segment(&v1, &v2));
+        for (s1, s2) in triangulator.segments.keys() {
+            assert_eq!(s1.y, 1.0);
+            assert_eq!(s2.y, 1.0);
+            assert!(
+                triangulator
+                    .adjacency
+                    .contains_key(&(Rc::clone(s1), Rc::clone(s2)))
+                    || triangulator
+                        .adjacency
+                        .contains_key(&(Rc::clone(s2), Rc::clone(s1)))
+            );
+        }
+    }
+
+    #[test]
+    fn test_vertex_inserted_near_segment_keeps_it() {
+        let mut triangulator =
+            Triangulator::from_coordinates(vec![0.0, 0.0, 10.0, 0.0, 10.0, 10.0, 0.0, 10.0]);
+        triangulator.triangulate();
+        triangulator.insert_segment(
+            Rc::new(Vertex::new(0.0, 5.0)),
+            Rc::new(Vertex::new(10.0, 5.0)),
+        );
+        triangulator.insert_vertex(Rc::new(Vertex::new(5.0, 5.2)));
+
+        /* (5.0, 5.2) encroaches the segment, which is split to stay in the mesh */
+        let mut length = 0.0;
+        for (s1, s2) in triangulator.segments.keys() {
+            assert_eq!(s1.y, 5.0);
+            assert_eq!(s2.y, 5.0);
+            assert!(
+                triangulator
+                    .adjacency
+                    .contains_key(&(Rc::clone(s1), Rc::clone(s2)))
+                    || triangulator
+                        .adjacency
+                        .contains_key(&(Rc::clone(s2), Rc::clone(s1)))
+            );
+            length += (s2.x - s1.x).abs();
+        }
+        assert_eq!(length, 10.0);
+        assert!(triangulator.contains_vertex(&Rc::new(Vertex::new(5.0, 5.2))));
+    }
+}
+
+#[cfg(test)]
+mod insert_hole_seed {
+    use super::*;
+
+    #[test]
+    fn test_flood_fills_from_seed() {
+        let mut triangulator = Triangulator::from_coordinates(vec![
+            0.0, 0.0, 10.0, 0.0, 10.0, 10.0, 0.0, 10.0, 5.0, 5.0, 4.5, 5.5,
+        ]);
+        triangulator.triangulate();
+
+        let hole_path = Vertex::from_coordinates(vec![3.0, 3.0, 7.0, 3.0, 7.0, 7.0, 3.0, 7.0]);
+        for index in 0..hole_path.len() {
+            let v1 = Rc::clone(&hole_path[index]);
+            let v2 = Rc::clone(&hole_path[(index + 1) % hole_path.len()]);
+            triangulator.insert_segment(v1, v2);
+        }
+        triangulator.insert_hole_seed(Rc::new(Vertex::new(4.0, 4.0)));
+
+        let area: f64 = triangulator
+            .triangles
+            .iter()
+            .map(|triangle| triangle.area())
+            .sum();
+        assert!((area - 84.0).abs() < 1e-9);
+
+        /* vertices inside the hole are now surrounded by ghost triangles only */
+        let ghost_count = triangulator
+            .triangles
+            .iter()
+            .filter(|triangle| triangle.is_ghost())
+            .count();
+        assert_eq!(ghost_count, 4 + triangulator.segments.len());
+    }
+
+    #[test]
+    fn test_seed_at_hull() {
+        let mut triangulator =
+            Triangulator::from_coordinates(vec![0.0, 0.0, 4.0, 0.0, 5.0, 2.0, 4.0, 4.0, 0.0, 3.0]);
+        triangulator.triangulate();
+        triangulator.insert_segment(
+            Rc::new(Vertex::new(4.0, 0.0)),
+            Rc::new(Vertex::new(4.0, 4.0)),
+        );
+        triangulator.insert_hole_seed(Rc::new(Vertex::new(4.5, 2.0)));
+
+        /* ghost triangles facing the removed triangle go with it */
+        let ghosts: Vec<&Rc<Triangle>> = triangulator
+            .triangles
+            .iter()
+            .filter(|triangle| triangle.is_ghost())
+            .collect();
+        assert_eq!(ghosts.len(), 4);
+        for ghost in ghosts {
+            for (v1, v2) in ghost.edges() {
+                if !v1.is_ghost && !v2.is_ghost {
+                    assert!(!triangulator.adjacency[&(v2, v1)].is_ghost());
+                }
+            }
+        }
+        assert!((triangulator.convex_hull().area() - 14.0).abs() < 1e-9);
+    }
+}
+
+#[cfg(test)]
+mod boundary_markers {
+    use super::*;
+
+    #[test]
+    fn test_steiner_points_inherit_segment_marker() {
+        let mut triangulator = Triangulator::from_coordinates(vec![
+            0.0, 0.0, 10.0, 0.0, 10.0, 10.0, 0.0, 10.0, 5.0, 4.9, 5.0, 5.1,
+        ]);
+        triangulator.triangulate();
+        triangulator.insert_marked_segment(
+            Rc::new(Vertex::new(0.0, 5.0)),
+            Rc::new(Vertex::new(10.0, 5.0)),
+            2,
+        );
+
+        let triangulation = triangulator.export();
+        let coordinates = &triangulation.coordinates;
+        assert_eq!(
+            triangulation.vertex_markers.len(),
+            triangulation.coordinates.len() / 2
+        );
+
+        /* Steiner points were created on the segment */
+        let marked_vertices: Vec<usize> = (0..coordinates.len() / 2)
+            .filter(|&index| triangulation.vertex_markers[index] == 2)
+            .collect();
+        assert!(marked_vertices.len() > 2);
+        for index in marked_vertices.iter() {
+            assert_eq!(coordinates[index * 2 + 1], 5.0);
+        }
+
+        /* subsegments cover the whole segment, once each */
+        let mut marked_length = 0.0;
+        for (index, edge) in triangulation.boundary_edges.chunks(2).enumerate() {
+            if triangulation.boundary_markers[index] != 2 {
+                continue;
+            }
+            assert_eq!(coordinates[edge[0] * 2 + 1], 5.0);
+            assert_eq!(coordinates[edge[1] * 2 + 1], 5.0);
+            marked_length += (coordinates[edge[0] * 2] - coordinates[edge[1] * 2]).abs();
+        }
+        assert_eq!(marked_length, 10.0);
+    }
+
+    #[test]
+    fn test_hull_edges_are_exported_unmarked() {
+        let mut triangulator =
+            Triangulator::from_coordinates(vec![0.0, 0.0, 10.0, 0.0, 10.0, 10.0, 0.0, 10.0]);
+        triangulator.triangulate();
+        triangulator.insert_marked_segment(
+            Rc::new(Vertex::new(0.0, 0.0)),
+            Rc::new(Vertex::new(10.0, 0.0)),
+            1,
+        );
+        triangulator.set_vertex_marker(Rc::new(Vertex::new(10.0, 0.0)), 3);
+
+        let triangulation = triangulator.export();
+        assert_eq!(triangulation.boundary_edges.len(), 8);
+
+        let mut markers = triangulation.boundary_markers.clone();
+        markers.sort();
+        assert_eq!(markers, vec![0, 0, 0, 1]);
+
+        /* vertices are sorted by position */
+        assert_eq!(triangulation.vertex_markers, vec![1, 0, 3, 0]);
+    }
+}
+
+#[cfg(test)]
+mod insert_region {
+    use super::*;
+
+    fn split_square() -> Triangulator {
+        let mut triangulator =
+            Triangulator::from_coordinates(vec![0.0, 0.0, 10.0, 0.0, 10.0, 10.0, 0.0, 10.0]);
+        triangulator.triangulate();
+        triangulator.insert_segment(
+            Rc::new(Vertex::new(5.0, 0.0)),
+            Rc::new(Vertex::new(5.0, 10.0)),
+        );
+        triangulator
+    }
+
+    #[test]
+    fn test_regions_are_bounded_by_segments() {
+        let mut triangulator = split_square();
+        triangulator.insert_region(Rc::new(Vertex::new(2.0, 5.0)), 1.0, None);
+        triangulator.insert_region(Rc::new(Vertex::new(8.0, 5.0)), 2.0, None);
+
+        let triangulation = triangulator.export();
+        assert_eq!(
+            triangulation.attributes.len(),
+            triangulation.triangles.len() / 3
+        );
+
+        for (index, triangle) in triangulation.triangles.chunks(3).enumerate() {
+            let centroid_x: f64 = triangle
+                .iter()
+                .map(|vertex| triangulation.coordinates[vertex * 2])
+                .sum::<f64>()
+                / 3.0;
+            let expected = if centroid_x < 5.0 { 1.0 } else { 2.0 };
+            assert_eq!(triangulation.attributes[index], expected);
+        }
+    }
+
+    #[test]
+    fn test_refines_region_to_max_area() {
+        let mut triangulator = split_square();
+        triangulator.insert_region(Rc::new(Vertex::new(8.0, 5.0)), 2.0, Some(4.0));
+
+        let mut left_triangles = 0;
+        for triangle in triangulator.triangles.iter() {
+            if triangle.is_ghost() {
+                continue;
+            }
+            let centroid_x = (triangle.v1.x + triangle.v2.x + triangle.v3.x) / 3.0;
+            if centroid_x > 5.0 {
+                assert!(triangle.area() <= 4.0);
+            } else {
+                left_triangles += 1;
+            }
+        }
+        assert!(left_triangles < 10);
+
+        let triangulation = triangulator.export();
+        let untagged = triangulation
+            .attributes
+            .iter()
+            .filter(|&&attribute| attribute == 0.0)
+            .count();
+        assert_eq!(untagged, left_triangles);
+    }
+
+    #[test]
+    fn test_refines_region_next_to_hole() {
+        let mut triangulator =
+            Triangulator::from_coordinates(vec![0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0]);
+        triangulator.triangulate();
+        triangulator.insert_hole(Vertex::from_coordinates(vec![
+            0.4, 0.4, 0.6, 0.4, 0.6, 0.6, 0.4, 0.6,
+        ]));
+        triangulator.insert_region(Rc::new(Vertex::new(0.1, 0.1)), 1.0, Some(0.03));
+
+        let mut area = 0.0;
+        for triangle in triangulator.triangles.iter() {
+            if triangle.is_ghost() {
+                continue;
+            }
+            assert_eq!(
+                orient_2d(&triangle.v1, &triangle.v2, &triangle.v3),
+                Orientation::Counterclockwise
+            );
+            assert!(triangle.area() <= 0.03);
+            area += triangle.area();
+        }
+        assert!((area - 0.96).abs() < 1e-9);
+    }
+}
+
+#[cfg(test)]
+mod export {
+    use super::*;
+
+    #[test]
+    fn test_export_vertices() {
+        let vertex_indices = vec![0.0, 0.0, 2.0, 0.0, 1.0, 2.0, 1.0, 1.0];
+        let mut triangulator = Triangulator::from_coordinates(vertex_indices);
+        triangulator.triangulate();
+        let triangulation = triangulator.export();
+        println!("{}", triangulation);
+        assert!(
+            triangulation
+                .coordinates
+                .chunks(2)
+                .position(|slice| slice == [0.0, 0.0])
+                != None
+        );
+        assert!(
+            triangulation
+                .coordinates
+                .chunks(2)
+                .position(|slice| slice == [2.0, 0.0])
+                != None
+        );
+        assert!(
+            triangulation
+                .coordinates
+                .chunks(2)
+                .position(|slice| slice == [1.0, 2.0])
+                != None
+        );
+        assert!(
+            triangulation
+                .coordinates
+                .chunks(2)
+                .position(|slice| slice == [1.0, 1.0])
+                != None
+        );
+    }
+
+    #[test]
+    fn test_export_triangles() {
+        let vertex_indices = vec![0.0, 0.0, 2.0, 0.0, 1.0, 2.0, 1.0, 1.0];
+        let mut triangulator = Triangulator::from_coordinates(vertex_indices);
+        triangulator.triangulate();
+        let triangulation = triangulator.export();
+        println!("{}", triangulation);
+        assert!(
+            triangulation
+                .triangles
+                .chunks(3)
+                .position(|slice| slice == [0, 3, 1])
+                != None
+        );
+        assert!(
+            triangulation
+                .triangles
+                .chunks(3)
+                .position(|slice| slice == [0, 1, 2])
+                != None
+        );
+        assert!(
+            triangulation
+                .triangles
+                .chunks(3)
+                .position(|slice| slice == [1, 3, 2])
+                != None
+        );
+    }
+}
+
+#[cfg(test)]
+mod snapshot {
+    use super::*;
+
+    fn sorted_triangles(triangulation: &Triangulation) -> Vec<Vec<usize>> {
+        let mut triangles: Vec<Vec<usize>> = triangulation
+            .triangles
+            .chunks(3)
+            .map(|triangle| {
+                /* rotated to start at the lowest index, keeping the orientation */
+                let first = (0..3).min_by_key(|corner| triangle[*corner]).unwrap();
+                (0..3)
+                    .map(|corner| triangle[(first + corner) % 3])
+                    .collect()
+            })
+            .collect();
+        triangles.sort();
+        triangles
+    }
+
+    #[test]
+    fn test_restores_mutable_mesh() {
+        let all_coordinates = scattered_coordinates(14);
+        let mut triangulator = Triangulator::from_coordinates(all_coordinates[..20].to_vec());
+        triangulator.triangulate();
+        triangulator.set_vertex_marker(
+            Rc::new(Vertex::new(all_coordinates[0], all_coordinates[1])),
+            7,
+        );
+
+        let mut restored = Triangulator::from_snapshot(triangulator.snapshot()).unwrap();
+        assert_eq!(restored.triangles.len(), triangulator.triangles.len());
+        assert_eq!(restored.adjacency.len(), triangulator.adjacency.len());
+
+        for point in all_coordinates[20..].chunks(2) {
+            restored.insert_vertex(Rc::new(Vertex::new(point[0], point[1])));
+        }
+        let mut expected = Triangulator::from_coordinates(all_coordinates);
+        expected.triangulate();
+
+        let restored = restored.export_preserving_order();
+        let expected = expected.export_preserving_order();
+        assert_eq!(restored.coordinates, expected.coordinates);
+        assert_eq!(sorted_triangles(&restored), sorted_triangles(&expected));
+        assert_eq!(restored.vertex_markers[0], 7);
+    }
+
+    #[test]
+    fn test_keeps_segments_and_regions() {
+        let mut triangulator = Triangulator::from_coordinates(scattered_coordinates(10));
+        triangulator.triangulate();
+        let (v1, v2) = (
+            triangulator.indexed_vertices[0].clone(),
+            triangulator.indexed_vertices[3].clone(),
+        );
+        triangulator.insert_marked_segment(v1, v2, 2);
+        triangulator.insert_region(Rc::new(Vertex::new(5.0, 5.0)), 4.0, None);
+
+        let restored = Triangulator::from_snapshot(triangulator.snapshot()).unwrap();
+        assert!(restored.segments == triangulator.segments);
+        let restored = restored.export_preserving_order();
+        let expected = triangulator.export_preserving_order();
+        assert_eq!(sorted_triangles(&restored), sorted_triangles(&expected));
+        assert_eq!(
+            restored.boundary_markers.len(),
+            expected.boundary_markers.len()
+        );
+        assert!(restored.attributes.contains(&4.0));
+    }
+
+    #[test]
+    fn test_reports_invalid_snapshots() {
+        let mut triangulator = Triangulator::from_coordinates(scattered_coordinates(6));
+        triangulator.triangulate();
+        let error = |change: fn(&mut TriangulatorSnapshot)| {
+            let mut snapshot = triangulator.snapshot();
+            change(&mut snapshot);
+            Triangulator::from_snapshot(snapshot).err().unwrap()
+        };
+
+        let invalid = error(|snapshot| snapshot.triangles[4] = 6);
+        assert_eq!(
+            invalid.to_string(),
+            "line 2: index 6 out of range in triangles"
+        );
+        let invalid = error(|snapshot| {
+            snapshot.conflicts = vec![snapshot.triangles.len() / 3, 0];
+        });
+        assert_eq!(invalid.line, 1);
+        let invalid = error(|snapshot| snapshot.pending_vertices = vec![0, 9]);
+        assert_eq!(invalid.line, 2);
+        let invalid = error(|snapshot| {
+            snapshot.segments = vec![0, 1];
+        });
+        assert_eq!(invalid.message, "expected a marker for each segment");
+        let invalid = error(|snapshot| {
+            snapshot.triangles.pop();
+        });
+        assert_eq!(invalid.line, 0);
+        let invalid = error(|snapshot| {
+            snapshot.vertex_markers.pop();
+        });
+        assert_eq!(invalid.message, "expected a marker for each vertex");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serializes() {
+        let mut triangulator = Triangulator::from_coordinates(scattered_coordinates(8));
+        triangulator.triangulate();
+
+        let json = serde_json::to_string(&triangulator.snapshot()).unwrap();
+        let snapshot: TriangulatorSnapshot = serde_json::from_str(&json).unwrap();
+        let restored = Triangulator::from_snapshot(snapshot)
+            .unwrap()
+            .export_preserving_order();
+        let expected = triangulator.export_preserving_order();
+        assert_eq!(sorted_triangles(&restored), sorted_triangles(&expected));
+
+        let json = serde_json::to_string(&expected).unwrap();
+        let triangulation: Triangulation = serde_json::from_str(&json).unwrap();
+        assert_eq!(triangulation.triangles, expected.triangles);
+        assert_eq!(triangulation.neighbors, expected.neighbors);
+    }
+}
+
+#[cfg(test)]
+mod voronoi {
+    use super::*;
+
+    #[test]
+    fn test_bounded_cell_of_center() {
+        let vertex_indices = vec![0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0, 0.5, 0.5];
+        let mut triangulator = Triangulator::from_coordinates(vertex_indices);
+        triangulator.triangulate();
+        let voronoi = triangulator.voronoi();
+
+        assert_eq!(voronoi.cells.len(), 5);
+        let center_cell = &voronoi.cells[4];
+        assert!(center_cell.is_bounded());
+        assert_eq!(center_cell.vertices.len(), 8);
+        assert_eq!(center_cell.neighbors.len(), 4);
+        assert_eq!(polygon_area(&center_cell.vertices), 0.5);
+    }
+
+    #[test]
+    fn test_unbounded_cells_of_hull() {
+        let vertex_indices = vec![0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0, 0.5, 0.5];
+        let mut triangulator = Triangulator::from_coordinates(vertex_indices);
+        triangulator.triangulate();
+        let voronoi = triangulator.voronoi();
+
+        let corner_cell = &voronoi.cells[0];
+        assert!(!corner_cell.is_bounded());
+        assert_eq!(corner_cell.vertices, vec![0.5, 0.0, 0.0, 0.5]);
+        /* rays point away from the hull: below (0.5, 0) and left of (0, 0.5) */
+        assert_eq!(corner_cell.rays, vec![0.0, -1.0, -1.0, 0.0]);
+        assert_eq!(corner_cell.neighbors.len(), 3);
+    }
+
+    #[test]
+    fn test_clipped_cells_cover_box() {
+        let vertex_indices = vec![
+            1.0, 0.0, 2.0, 0.0, 3.0, 1.0, 2.0, 2.0, 1.0, 2.0, 0.0, 1.0, 1.2, 1.0, 2.0, 1.0,
+        ];
+        let mut triangulator = Triangulator::from_coordinates(vertex_indices);
+        triangulator.triangulate();
+        let voronoi = triangulator.voronoi();
+
+        let clipped_cells = voronoi.clip_to_box(-1.0, -1.0, 4.0, 3.0);
+        let total_area: f64 = clipped_cells.iter().map(|cell| polygon_area(cell)).sum();
+        assert!((total_area - 20.0).abs() < 1e-9);
+
+        let corner_cell = voronoi.cells[0].clip_to_box(0.0, 0.0, 1.0, 1.0);
+        assert!(polygon_area(&corner_cell) > 0.0);
+    }
+
+    #[test]
+    fn test_keeps_indices_after_deletion() {
+        let vertex_indices = vec![0.0, 0.0, 3.0, 0.0, 3.0, 2.0, 0.0, 2.0, 1.2, 0.9, 1.9, 1.1];
+        let mut triangulator = Triangulator::from_coordinates(vertex_indices);
+        triangulator.triangulate();
+        triangulator.delete_vertex(Rc::new(Vertex::new(1.2, 0.9)));
+        let voronoi = triangulator.voronoi();
+
+        assert_eq!(voronoi.cells.len(), 6);
+        for (cell, site) in voronoi
+            .cells
+            .iter()
+            .zip(triangulator.indexed_vertices.iter())
+        {
+            assert!(cell.site == *site);
+        }
+        assert!(voronoi.cells[4].is_empty());
+        assert!(!voronoi.cells[4].is_bounded());
+        assert!(voronoi.cells[5].is_bounded());
+
+        /* the empty cell takes no part of the polygon */
+        let rectangle = vec![0.0, 0.0, 3.0, 0.0, 3.0, 2.0, 0.0, 2.0];
+        let clipped_cells = voronoi.clip(&rectangle);
+        assert!(clipped_cells[4].is_empty());
+        let total_area: f64 = clipped_cells.iter().map(|cell| polygon_area(cell)).sum();
+        assert!((total_area - 6.0).abs() < 1e-9);
+    }
+}
+
+#[cfg(test)]
+mod convex_hull {
+    use super::*;
+
+    #[test]
+    fn test_hull_of_triangulation() {
+        let vertex_indices = vec![0.0, 0.0, 1.0, 1.0, 1.0, 0.0, 0.0, 1.0, 0.5, 0.5, 0.5, 0.0];
+        let mut triangulator = Triangulator::from_coordinates(vertex_indices);
+        triangulator.triangulate();
+
+        let hull = triangulator.convex_hull();
+        assert_eq!(hull.vertices.len(), 5);
+        assert_eq!(hull.area(), 1.0);
+        assert_eq!(hull.perimeter(), 4.0);
+        assert_eq!(hull.contains(&Vertex::new(0.5, 0.5)), Continence::Inside);
+        assert_eq!(hull.contains(&Vertex::new(1.5, 0.5)), Continence::Outside);
+    }
+
+    #[test]
+    fn test_hull_ignores_holes() {
+        let vertex_indices = vec![0.0, 0.0, 4.0, 0.0, 4.0, 4.0, 0.0, 4.0];
+        let mut triangulator = Triangulator::from_coordinates(vertex_indices);
+        triangulator.triangulate();
+        triangulator.insert_hole(vec![
+            Rc::new(Vertex::new(1.0, 1.0)),
+            Rc::new(Vertex::new(3.0, 1.0)),
+            Rc::new(Vertex::new(3.0, 3.0)),
+            Rc::new(Vertex::new(1.0, 3.0)),
+        ]);
+
+        let hull = triangulator.convex_hull();
+        assert_eq!(hull.area(), 16.0);
+        assert_eq!(hull.contains(&Vertex::new(2.0, 2.0)), Continence::Inside);
+    }
+
+    #[test]
+    fn test_hull_spans_concave_boundary() {
+        let outer = Vertex::from_coordinates(vec![
+            0.0, 0.0, 6.0, 0.0, 6.0, 2.0, 2.0, 2.0, 2.0, 6.0, 0.0, 6.0,
+        ]);
+        let mut triangulator = Triangulator::from_vertices(outer.clone());
+        triangulator.triangulate();
+        assert_eq!(triangulator.convex_hull().area(), 28.0);
+
+        triangulator.insert_boundary(&[outer]);
+        let hull = triangulator.convex_hull();
+        assert_eq!(hull.vertices.len(), 5);
+        assert_eq!(hull.area(), 28.0);
+        assert_eq!(hull.contains(&Vertex::new(4.0, 3.0)), Continence::Inside);
+        assert_eq!(hull.contains(&Vertex::new(5.0, 4.0)), Continence::Outside);
+    }
+}
+
+#[cfg(test)]
+mod locate {
+    use super::*;
+
+    fn square_with_hole() -> Triangulator {
+        let vertex_indices = vec![0.0, 0.0, 4.0, 0.0, 4.0, 4.0, 0.0, 4.0, 0.5, 3.5, 3.5, 0.5];
+        let mut triangulator = Triangulator::from_coordinates(vertex_indices);
+        triangulator.triangulate();
+        triangulator.insert_hole(vec![
+            Rc::new(Vertex::new(1.0, 1.0)),
+            Rc::new(Vertex::new(3.0, 1.0)),
+            Rc::new(Vertex::new(3.0, 3.0)),
+            Rc::new(Vertex::new(1.0, 3.0)),
+        ]);
+        triangulator
+    }
+
+    #[test]
+    fn test_nothing_to_locate() {
+        let triangulator = Triangulator::new();
+        assert!(triangulator.locate(0.0, 0.0).is_none());
+    }
+
+    #[test]
+    fn test_inside_triangle() {
+        let triangulator = square_with_hole();
+        match triangulator.locate(0.2, 0.3) {
+            Some(Location::Inside(triangle)) => {
+                assert_eq!(
+                    triangle.contains(&Vertex::new(0.2, 0.3)),
+                    Continence::Inside
+                )
+            }
+            _ => panic!("expected a triangle"),
+        }
+    }
+
+    #[test]
+    fn test_on_vertex_and_edge() {
+        let triangulator = square_with_hole();
+        match triangulator.locate(3.0, 3.0) {
+            Some(Location::OnVertex(vertex)) => assert!(*vertex == Vertex::new(3.0, 3.0)),
+            _ => panic!("expected a vertex"),
+        }
+        match triangulator.locate(2.0, 0.0) {
+            Some(Location::OnEdge(triangle, v1, v2)) => {
+                assert!(!triangle.is_ghost());
+                assert_eq!(v1.y, 0.0);
+                assert_eq!(v2.y, 0.0);
+            }
+            _ => panic!("expected an edge"),
+        }
+    }
+
+    #[test]
+    fn test_outside_hull_and_in_hole() {
+        let triangulator = square_with_hole();
+        match triangulator.locate(2.0, -1.0) {
+            Some(Location::Outside(v1, v2)) => {
+                assert_eq!(segment_distance(&v1, &v2, &Vertex::new(2.0, -1.0)), 1.0)
+            }
+            _ => panic!("expected outside"),
+        }
+        match triangulator.locate(2.0, 2.8) {
+            Some(Location::Outside(v1, v2)) => {
+                assert_eq!((v1.y, v2.y), (3.0, 3.0));
+            }
+            _ => panic!("expected outside"),
+        }
+    }
+
+    #[test]
+    fn test_agrees_with_scan() {
+        let triangulator = square_with_hole();
+        for step in 0..400 {
+            let (x, y) = ((step % 20) as f64 * 0.21, (step / 20) as f64 * 0.21);
+            let point = Vertex::new(x, y);
+            match triangulator.locate(x, y) {
+                Some(Location::Inside(triangle)) => {
+                    assert_eq!(triangle.contains(&point), Continence::Inside)
+                }
+                Some(Location::OnEdge(triangle, _, _)) => {
+                    assert_eq!(triangle.contains(&point), Continence::Boundary)
+                }
+                Some(Location::OnVertex(vertex)) => assert!(*vertex == point),
+                Some(Location::Outside(_, _)) => {
+                    assert!(triangulator.find_containing_triangle(&point).is_none())
+                }
+                None => panic!("expected a location"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod nearest {
+    use super::*;
+
+    fn scattered() -> Triangulator {
+        let mut vertex_indices = Vec::new();
+        for index in 0..40 {
+            vertex_indices.push((index * 37 % 101) as f64 / 10.0);
+            vertex_indices.push((index * 59 % 97) as f64 / 10.0);
+        }
+        let mut triangulator = Triangulator::from_coordinates(vertex_indices);
+        triangulator.triangulate();
+        triangulator
+    }
+
+    fn sorted_distances(triangulator: &Triangulator, x: f64, y: f64) -> Vec<(usize, f64)> {
+        let mut distances: Vec<(usize, f64)> = triangulator
+            .indexed_vertices
+            .iter()
+            .enumerate()
+            .map(|(index, vertex)| (index, (vertex.x - x).hypot(vertex.y - y)))
+            .collect();
+        distances.sort_by(|(_, d1), (_, d2)| d1.partial_cmp(d2).unwrap());
+        distances
+    }
+
+    #[test]
+    fn test_nearest() {
+        let triangulator = scattered();
+        assert!(Triangulator::new().nearest(0.0, 0.0).is_none());
+        for (x, y) in [(0.0, 0.0), (5.1, 4.3), (9.9, 0.2), (-3.0, 12.0), (3.7, 5.9)].iter() {
+            let (_, distance) = triangulator.nearest(*x, *y).unwrap();
+            assert_eq!(distance, sorted_distances(&triangulator, *x, *y)[0].1);
+        }
+    }
+
+    #[test]
+    fn test_nearest_on_vertex() {
+        let triangulator = scattered();
+        let vertex = &triangulator.indexed_vertices[7];
+        assert_eq!(triangulator.nearest(vertex.x, vertex.y), Some((7, 0.0)));
+    }
+
+    #[test]
+    fn test_k_nearest() {
+        let triangulator = scattered();
+        for (x, y) in [(2.2, 8.1), (5.0, 5.0), (11.0, -1.0)].iter() {
+            let distances: Vec<f64> = triangulator
+                .k_nearest(*x, *y, 6)
+                .iter()
+                .map(|(_, distance)| *distance)
+                .collect();
+            let expected: Vec<f64> = sorted_distances(&triangulator, *x, *y)[..6]
+                .iter()
+                .map(|(_, distance)| *distance)
+                .collect();
+            assert_eq!(distances, expected);
+        }
+        assert_eq!(triangulator.k_nearest(1.0, 1.0, 100).len(), 40);
+    }
+
+    #[test]
+    fn test_k_nearest_whatever_the_insertion_order() {
+        /* grid-like points hold many cocircular quadruples, and hashing changes
+        the order ties are met in from one triangulation to the next */
+        for _ in 0..10 {
+            let triangulator = scattered();
+            for triangle in triangulator.triangles.iter() {
+                for vertex in triangulator.indexed_vertices.iter() {
+                    assert!(triangle.encircles(vertex) != Continence::Inside);
+                }
+            }
+            for (x, y) in [(2.2, 8.1), (5.0, 5.0), (3.3, 4.4)].iter() {
+                let distances: Vec<f64> = triangulator
+                    .k_nearest(*x, *y, 8)
+                    .iter()
+                    .map(|(_, distance)| *distance)
+                    .collect();
+                let expected: Vec<f64> = sorted_distances(&triangulator, *x, *y)[..8]
+                    .iter()
+                    .map(|(_, distance)| *distance)
+                    .collect();
+                assert_eq!(distances, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_within_radius() {
+        let triangulator = scattered();
+        let found = triangulator.within_radius(4.0, 6.0, 2.5);
+        let expected: Vec<(usize, f64)> = sorted_distances(&triangulator, 4.0, 6.0)
+            .into_iter()
+            .filter(|(_, distance)| *distance <= 2.5)
+            .collect();
+        assert_eq!(found.len(), expected.len());
+        for (index, distance) in found.iter() {
+            assert!(expected.contains(&(*index, *distance)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod interpolate {
+    use super::*;
+
+    fn plane(x: f64, y: f64) -> f64 {
+        2.0 * x - 3.0 * y + 1.0
+    }
+
+    fn sampled_plane() -> Triangulator {
+        let mut vertex_indices = vec![0.0, 0.0, 10.0, 0.0, 10.0, 10.0, 0.0, 10.0];
+        for index in 0..20 {
+            vertex_indices.push(0.5 + (index * 37 % 91) as f64 / 10.0);
+            vertex_indices.push(0.5 + (index * 59 % 89) as f64 / 10.0);
+        }
+        let values: Vec<f64> = vertex_indices
+            .chunks(2)
+            .map(|chunk| plane(chunk[0], chunk[1]))
+            .collect();
+        let mut triangulator = Triangulator::from_coordinates(vertex_indices);
+        triangulator.triangulate();
+        triangulator.set_vertex_values(&values);
+        triangulator
+    }
+
+    #[test]
+    fn test_linear_reproduces_plane() {
+        let triangulator = sampled_plane();
+        for (x, y) in [(1.0, 1.0), (3.3, 7.1), (9.9, 0.1), (5.0, 0.0)].iter() {
+            let value = triangulator.interpolate_linear(*x, *y).unwrap();
+            assert!((value - plane(*x, *y)).abs() < 1e-9);
+        }
+        assert!(triangulator.interpolate_linear(11.0, 5.0).is_none());
+    }
+
+    #[test]
+    fn test_natural_neighbor_reproduces_plane() {
+        let triangulator = sampled_plane();
+        for (x, y) in [(1.0, 1.0), (3.3, 7.1), (9.9, 0.1), (5.0, 0.0), (0.0, 10.0)].iter() {
+            let value = triangulator.interpolate_natural_neighbor(*x, *y).unwrap();
+            assert!((value - plane(*x, *y)).abs() < 1e-9);
+        }
+        assert!(triangulator
+            .interpolate_natural_neighbor(-1.0, 5.0)
+            .is_none());
+    }
+
+    #[test]
+    fn test_natural_neighbor_weights_by_stolen_area() {
+        let vertex_indices = vec![0.0, 0.0, 2.0, 0.0, 2.0, 2.0, 0.0, 2.0, 1.0, 1.0];
+        let mut triangulator = Triangulator::from_coordinates(vertex_indices);
+        triangulator.triangulate();
+        triangulator.set_vertex_values(&[0.0, 0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(
+            triangulator.interpolate_natural_neighbor(1.0, 1.0),
+            Some(1.0)
+        );
+
+        /*
+           The new cell of (0.5, 0.5) has corners (1, -0.5), (1.25, 0.25),
+           (0.25, 1.25) and (-0.5, 1), so its area is 1.25. It takes 0.625 from
+           the cell of (0, 0), 0.0625 from the cells of (2, 0) and (0, 2), and 0.5
+           from the cell of (1, 1), while (2, 2) is no natural neighbour. Linear
+           interpolation would weigh (0, 0) and (1, 1) by half each instead.
         */
+        let expected_weights = [0.5, 0.05, 0.0, 0.05, 0.4];
+        for (index, expected_weight) in expected_weights.iter().enumerate() {
+            triangulator.set_vertex_values(&[0.0; 5]);
+            let vertex = Rc::clone(&triangulator.indexed_vertices[index]);
+            triangulator.set_vertex_value(vertex, 1.0);
+            let weight = triangulator.interpolate_natural_neighbor(0.5, 0.5).unwrap();
+            assert!((weight - expected_weight).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_missing_values() {
+        let vertex_indices = vec![0.0, 0.0, 2.0, 0.0, 0.0, 2.0];
+        let mut triangulator = Triangulator::from_coordinates(vertex_indices);
+        triangulator.triangulate();
+        triangulator.set_vertex_values(&[1.0, 2.0]);
+        assert!(triangulator.interpolate_linear(0.5, 0.5).is_none());
+        assert!(triangulator
+            .interpolate_natural_neighbor(0.5, 0.5)
+            .is_none());
+    }
+}
+
+#[cfg(test)]
+mod terrain {
+    use super::*;
+
+    fn pyramid() -> Triangulator {
+        let vertex_indices = vec![
+            0.0, 0.0, 0.0, 2.0, 0.0, 0.0, 2.0, 2.0, 0.0, 0.0, 2.0, 0.0, 1.0, 1.0, 3.0,
+        ];
+        let mut triangulator = Triangulator::from_coordinates_3d(vertex_indices);
+        triangulator.triangulate();
+        triangulator
+    }
 
+    #[test]
+    fn test_exports_elevations() {
+        let triangulation = pyramid().export_preserving_order();
+        assert_eq!(triangulation.z, vec![0.0, 0.0, 0.0, 0.0, 3.0]);
+
+        let mut triangulator = Triangulator::from_coordinates(vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0]);
         triangulator.triangulate();
-        triangulator.insert_hole(hole_path);
+        assert!(triangulator.export().z.is_empty());
+    }
 
-        assert_eq!(triangulator.vertices_size(), 6);
-        assert_eq!(triangulator.triangles_size(), 6);
+    #[test]
+    fn test_surface_area_and_volume() {
+        let triangulator = pyramid();
+        let face_area = 2.0 * 10.0_f64.sqrt() / 2.0;
+        assert!((triangulator.surface_area().unwrap() - 4.0 * face_area).abs() < 1e-9);
+        assert!((triangulator.volume_above(0.0).unwrap() - 4.0).abs() < 1e-9);
+        assert!((triangulator.volume_above(1.5).unwrap() - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_steiner_vertices_get_elevation() {
+        let mut triangulator = pyramid();
+        triangulator.insert_region(Rc::new(Vertex::new(1.0, 0.5)), 1.0, Some(0.3));
+
+        let triangulation = triangulator.export();
+        assert!(triangulation.coordinates.len() > 10);
+        assert_eq!(triangulation.z.len(), triangulation.coordinates.len() / 2);
     }
 }
 
 #[cfg(test)]
-mod export {
+mod alpha_shape {
     use super::*;
 
+    /* an L shaped footprint sampled on a grid with a spacing of 1 */
+    fn footprint() -> Triangulator {
+        let mut vertex_indices = Vec::new();
+        for x in 0..6 {
+            for y in 0..6 {
+                if x < 2 || y < 2 {
+                    vertex_indices.push(x as f64 + 0.01 * ((x * 7 + y * 3) % 5) as f64);
+                    vertex_indices.push(y as f64 + 0.01 * ((x * 3 + y * 5) % 7) as f64);
+                }
+            }
+        }
+        let mut triangulator = Triangulator::from_coordinates(vertex_indices);
+        triangulator.triangulate();
+        triangulator
+    }
+
     #[test]
-    fn test_export_vertices() {
-        let vertex_indices = vec![0.0, 0.0, 2.0, 0.0, 1.0, 2.0, 1.0, 1.0];
+    fn test_large_alpha_gives_convex_hull() {
+        let triangulator = footprint();
+        let shape = triangulator.alpha_shape(f64::INFINITY);
+        assert_eq!(shape.polygons.len(), 1);
+        assert!(shape.polygons[0].holes.is_empty());
+        let hull_area = triangulator.convex_hull().area();
+        assert!((polygon_area(&shape.polygons[0].exterior) - hull_area).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_concave_outline() {
+        let triangulator = footprint();
+        let shape = triangulator.alpha_shape(1.0);
+        assert_eq!(shape.polygons.len(), 1);
+        let area = polygon_area(&shape.polygons[0].exterior);
+        assert!(area > 8.5 && area < 9.5);
+        let triangles_area: f64 = shape.triangles.iter().map(|triangle| triangle.area()).sum();
+        assert!((area - triangles_area).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_small_alpha_keeps_nothing() {
+        let shape = footprint().alpha_shape(0.1);
+        assert!(shape.triangles.is_empty());
+        assert!(shape.polygons.is_empty());
+    }
+
+    #[test]
+    fn test_hole() {
+        /* a ring, wobbling so that no four points are cocircular */
+        let mut vertex_indices = Vec::new();
+        for index in 0..12 {
+            let angle = index as f64 * std::f64::consts::PI / 6.0;
+            let wobble = 0.1 * (index as f64 * 1.3).sin();
+            vertex_indices.push((4.0 + wobble) * angle.cos());
+            vertex_indices.push((4.0 + wobble) * angle.sin());
+            vertex_indices.push((3.0 - wobble) * (angle + 0.2).cos());
+            vertex_indices.push((3.0 - wobble) * (angle + 0.2).sin());
+        }
         let mut triangulator = Triangulator::from_coordinates(vertex_indices);
         triangulator.triangulate();
-        let triangulation = triangulator.export();
-        println!("{}", triangulation);
-        assert!(
-            triangulation
-                .coordinates
-                .chunks(2)
-                .position(|slice| slice == [0.0, 0.0])
-                != None
+
+        let shape = triangulator.alpha_shape(1.5);
+        assert_eq!(shape.polygons.len(), 1);
+        assert_eq!(shape.polygons[0].holes.len(), 1);
+        assert!(polygon_area(&shape.polygons[0].holes[0]) < 0.0);
+    }
+
+    #[test]
+    fn test_optimal_alpha() {
+        let triangulator = footprint();
+        let covered_size = |shape: &AlphaShape| {
+            shape
+                .triangles
+                .iter()
+                .flat_map(|triangle| {
+                    vec![
+                        Rc::clone(&triangle.v1),
+                        Rc::clone(&triangle.v2),
+                        Rc::clone(&triangle.v3),
+                    ]
+                })
+                .collect::<HashSet<Rc<Vertex>>>()
+                .len()
+        };
+
+        let alpha = triangulator.optimal_alpha().unwrap();
+        let shape = triangulator.alpha_shape(alpha);
+        assert_eq!(shape.alpha, alpha);
+        assert_eq!(shape.polygons.len(), 1);
+        assert_eq!(covered_size(&shape), 20);
+
+        let smaller_shape = triangulator.alpha_shape(alpha * 0.999);
+        assert!(smaller_shape.polygons.len() != 1 || covered_size(&smaller_shape) < 20);
+    }
+}
+
+#[cfg(test)]
+mod proximity_graphs {
+    use super::*;
+
+    fn scattered() -> (Vec<f64>, Triangulator) {
+        let coordinates = scattered_coordinates(30);
+        let mut triangulator = Triangulator::from_coordinates(coordinates.clone());
+        triangulator.triangulate();
+        (coordinates, triangulator)
+    }
+
+    fn distance(coordinates: &[f64], i: usize, j: usize) -> f64 {
+        (coordinates[i * 2] - coordinates[j * 2])
+            .hypot(coordinates[i * 2 + 1] - coordinates[j * 2 + 1])
+    }
+
+    #[test]
+    fn test_graphs_match_brute_force() {
+        let (coordinates, triangulator) = scattered();
+        let size = coordinates.len() / 2;
+        let pairs: Vec<(usize, usize)> = (0..size)
+            .flat_map(|i| (i + 1..size).map(move |j| (i, j)))
+            .collect();
+
+        let gabriel: Vec<(usize, usize)> = pairs
+            .iter()
+            .filter(|(i, j)| {
+                (0..size).filter(|k| k != i && k != j).all(|k| {
+                    distance(&coordinates, *i, k).powi(2) + distance(&coordinates, *j, k).powi(2)
+                        > distance(&coordinates, *i, *j).powi(2)
+                })
+            })
+            .cloned()
+            .collect();
+        let found: Vec<(usize, usize)> = triangulator
+            .gabriel_graph()
+            .iter()
+            .map(|(i, j, _)| (*i, *j))
+            .collect();
+        assert_eq!(found, gabriel);
+
+        let relative_neighborhood: Vec<(usize, usize)> = pairs
+            .iter()
+            .filter(|(i, j)| {
+                (0..size).filter(|k| k != i && k != j).all(|k| {
+                    distance(&coordinates, *i, k).max(distance(&coordinates, *j, k))
+                        >= distance(&coordinates, *i, *j)
+                })
+            })
+            .cloned()
+            .collect();
+        let found: Vec<(usize, usize)> = triangulator
+            .relative_neighborhood_graph()
+            .iter()
+            .map(|(i, j, _)| (*i, *j))
+            .collect();
+        assert_eq!(found, relative_neighborhood);
+    }
+
+    #[test]
+    fn test_minimum_spanning_tree() {
+        let (coordinates, triangulator) = scattered();
+        let size = coordinates.len() / 2;
+        let tree = triangulator.minimum_spanning_tree();
+        assert_eq!(tree.len(), size - 1);
+
+        /* Prim over all pairs gives the same total length */
+        let mut in_tree = vec![false; size];
+        let mut best = vec![f64::INFINITY; size];
+        best[0] = 0.0;
+        let mut total = 0.0;
+        for _ in 0..size {
+            let next = (0..size)
+                .filter(|&k| !in_tree[k])
+                .min_by(|&a, &b| best[a].partial_cmp(&best[b]).unwrap())
+                .unwrap();
+            in_tree[next] = true;
+            total += best[next];
+            for (k, best) in best.iter_mut().enumerate() {
+                *best = best.min(distance(&coordinates, next, k));
+            }
+        }
+        let tree_length: f64 = tree.iter().map(|(_, _, length)| length).sum();
+        assert!((tree_length - total).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_minimum_spanning_tree_with_repeated_vertex() {
+        let vertex_indices = vec![0.0, 0.0, 4.0, 0.0, 4.0, 3.0, 4.0, 0.0, 0.0, 3.0, 1.0, 1.0];
+        let mut triangulator = Triangulator::from_coordinates(vertex_indices);
+        triangulator.triangulate();
+
+        /* the repeated (4, 0) keeps its first index, the rest follow in order */
+        let tree = triangulator.minimum_spanning_tree();
+        assert_eq!(tree.len(), 4);
+        assert!(tree.iter().all(|(i, j, _)| *i != 3 && *j != 3));
+        assert!(tree
+            .iter()
+            .any(|(i, j, _)| (*i, *j) == (4, 5) || (*i, *j) == (5, 4)));
+    }
+
+    #[test]
+    fn test_subgraphs_of_delaunay() {
+        let (_, triangulator) = scattered();
+        let delaunay = triangulator.delaunay_edges();
+        let gabriel = triangulator.gabriel_graph();
+        assert_eq!(
+            delaunay.len(),
+            3 * 30 - 3 - triangulator.convex_hull().vertices.len()
         );
-        assert!(
-            triangulation
-                .coordinates
-                .chunks(2)
-                .position(|slice| slice == [2.0, 0.0])
-                != None
+        assert!(gabriel.iter().all(|edge| delaunay.contains(edge)));
+        assert!(triangulator
+            .relative_neighborhood_graph()
+            .iter()
+            .all(|edge| gabriel.contains(edge)));
+    }
+}
+
+#[cfg(test)]
+mod largest_empty_circle {
+    use super::*;
+
+    #[test]
+    fn test_within_hull() {
+        let vertex_indices = vec![0.0, 0.0, 4.0, 0.0, 4.0, 4.0, 0.0, 4.0, 1.0, 1.0];
+        let mut triangulator = Triangulator::from_coordinates(vertex_indices);
+        triangulator.triangulate();
+
+        /* circumcircles through (1, 1) and two corners, symmetric about the diagonal */
+        let (center, radius) = triangulator.largest_empty_circle().unwrap();
+        assert!(center == Vertex::new(3.0, 2.0) || center == Vertex::new(2.0, 3.0));
+        assert!((radius - 5.0_f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_within_polygon() {
+        let vertex_indices = vec![0.0, 0.0, 4.0, 0.0, 4.0, 4.0, 0.0, 4.0, 1.0, 1.0];
+        let mut triangulator = Triangulator::from_coordinates(vertex_indices);
+        triangulator.triangulate();
+
+        /* three corners of the polygon lie as far from (1, 1) */
+        let polygon = vec![0.0, 0.0, 2.0, 0.0, 2.0, 2.0, 0.0, 2.0];
+        let (center, radius) = triangulator.largest_empty_circle_in(&polygon).unwrap();
+        assert!(center != Vertex::new(0.0, 0.0) && polygon.contains(&center.x));
+        assert!((radius - 2.0_f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_nothing_triangulated() {
+        assert!(Triangulator::new().largest_empty_circle().is_none());
+    }
+}
+
+#[cfg(test)]
+mod debug_svg {
+    use super::*;
+
+    #[test]
+    fn test_draws_before_triangulating() {
+        let text = Triangulator::new().debug_svg(&SvgStyle::default());
+        assert!(!text.contains("<polygon") && !text.contains("<circle"));
+        assert!(text.trim_end().ends_with("</svg>"));
+
+        /* vertices are drawn as soon as they are given */
+        let triangulator = Triangulator::from_coordinates(vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0]);
+        let text = triangulator.debug_svg(&SvgStyle::default());
+        assert!(!text.contains("<polygon"));
+        assert_eq!(text.matches("<circle").count(), 3);
+    }
+
+    #[test]
+    fn test_draws_state() {
+        let vertex_indices = vec![0.0, 0.0, 4.0, 0.0, 4.0, 3.0, 0.0, 3.0, 1.3, 1.1];
+        let mut triangulator = Triangulator::from_coordinates(vertex_indices);
+        triangulator.triangulate();
+        triangulator.insert_segment(
+            Rc::new(Vertex::new(0.0, 0.0)),
+            Rc::new(Vertex::new(4.0, 3.0)),
         );
-        assert!(
-            triangulation
-                .coordinates
-                .chunks(2)
-                .position(|slice| slice == [1.0, 2.0])
-                != None
+
+        let style = SvgStyle {
+            show_circumcircles: true,
+            ..SvgStyle::default()
+        };
+        let text = triangulator.debug_svg(&style);
+        let ghosts_size = triangulator
+            .triangles
+            .iter()
+            .filter(|triangle| triangle.is_ghost())
+            .count();
+        let solid_size = triangulator.triangles.len() - ghosts_size;
+        assert_eq!(ghosts_size, 4);
+
+        let ghosts = text.split("stroke-dasharray=\"4 3\">").nth(1).unwrap();
+        let ghosts = ghosts.split("</g>").next().unwrap();
+        assert_eq!(ghosts.matches("<polygon").count(), ghosts_size);
+        assert_eq!(text.matches("<polygon").count(), solid_size + ghosts_size);
+        /* a circumcircle per solid triangle and a dot per vertex */
+        assert_eq!(
+            text.matches("<circle").count(),
+            solid_size + triangulator.indexed_vertices.len()
         );
-        assert!(
-            triangulation
-                .coordinates
-                .chunks(2)
-                .position(|slice| slice == [1.0, 1.0])
-                != None
+        let segments = format!(
+            "stroke=\"{}\" stroke-width=\"2\">\n<line",
+            style.segment_stroke
         );
+        assert!(text.contains(&segments));
     }
+}
+
+#[cfg(test)]
+mod relax {
+    use super::*;
 
     #[test]
-    fn test_export_triangles() {
-        let vertex_indices = vec![0.0, 0.0, 2.0, 0.0, 1.0, 2.0, 1.0, 1.0];
+    fn test_centers_single_vertex() {
+        let vertex_indices = vec![0.0, 0.0, 2.0, 0.0, 2.0, 2.0, 0.0, 2.0, 0.6, 1.3];
         let mut triangulator = Triangulator::from_coordinates(vertex_indices);
         triangulator.triangulate();
-        let triangulation = triangulator.export();
-        println!("{}", triangulation);
-        assert!(
-            triangulation
-                .triangles
-                .chunks(3)
-                .position(|slice| slice == [0, 3, 1])
-                != None
+
+        let iterations = triangulator.relax(50, 1e-9);
+        assert!(iterations < 50);
+
+        let triangulation = triangulator.export_preserving_order();
+        assert_eq!(
+            triangulation.coordinates[..8],
+            [0.0, 0.0, 2.0, 0.0, 2.0, 2.0, 0.0, 2.0]
         );
-        assert!(
-            triangulation
-                .triangles
-                .chunks(3)
-                .position(|slice| slice == [0, 1, 2])
-                != None
+        assert!((triangulation.coordinates[8] - 1.0).abs() < 1e-6);
+        assert!((triangulation.coordinates[9] - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_keeps_boundary_and_mesh_valid() {
+        let mut vertex_indices = vec![0.0, 0.0, 10.0, 0.0, 10.0, 10.0, 0.0, 10.0];
+        for index in 0..12 {
+            let angle = index as f64 * 2.4;
+            vertex_indices.push(5.0 + 3.0 * angle.cos() * (index as f64 / 12.0));
+            vertex_indices.push(5.0 + 3.0 * angle.sin() * (index as f64 / 12.0));
+        }
+        let mut triangulator = Triangulator::from_coordinates(vertex_indices);
+        triangulator.triangulate();
+        triangulator.relax(5, 0.0);
+
+        let area: f64 = triangulator
+            .triangles
+            .iter()
+            .map(|triangle| triangle.area())
+            .sum();
+        assert!((area - 100.0).abs() < 1e-9);
+        assert_eq!(triangulator.triangles_size(), 2 * 16 - 4 - 2);
+        for triangle in triangulator.triangles.iter() {
+            assert!(triangle.area() >= 0.0);
+        }
+    }
+
+    #[test]
+    fn test_keeps_mesh_valid_around_hole() {
+        let mut triangulator = Triangulator::from_coordinates(vec![
+            0.0, 0.0, 10.0, 0.0, 10.0, 10.0, 0.0, 10.0, 2.0, 7.1, 2.3, 5.8, 2.5, 6.3, 4.0, 6.9,
+            7.0, 7.5, 3.0, 8.1, 4.4, 8.2, 8.1, 9.8, 1.2, 5.3, 10.0, 6.4, 5.8, 6.8, 5.9, 8.9, 9.0,
+            8.9, 6.2, 7.4, 5.9, 9.7, 0.4, 8.4, 8.1, 6.2, 1.4, 7.2, 6.5, 3.4, 6.9, 8.2, 6.8, 7.9,
+            2.5, 7.1, 8.3, 9.5, 4.0, 1.0,
+        ]);
+        triangulator.triangulate();
+        triangulator.insert_hole(Vertex::from_coordinates(vec![
+            4.0, 4.0, 6.0, 4.0, 6.0, 6.0, 4.0, 6.0,
+        ]));
+        let before = triangulator.export_preserving_order().coordinates;
+        triangulator.relax(10, 0.0);
+
+        /* vertices moved, around the hole but not into it */
+        assert!(triangulator.export_preserving_order().coordinates != before);
+        let mut area = 0.0;
+        for triangle in triangulator.triangles.iter() {
+            if triangle.is_ghost() {
+                continue;
+            }
+            assert_eq!(
+                orient_2d(&triangle.v1, &triangle.v2, &triangle.v3),
+                Orientation::Counterclockwise
+            );
+            area += triangle.area();
+        }
+        assert!((area - 96.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_keeps_markers_values_and_segments() {
+        let mut triangulator = Triangulator::from_coordinates(vec![
+            0.0, 0.0, 10.0, 0.0, 10.0, 10.0, 0.0, 10.0, 2.3, 2.1, 7.4, 2.8, 6.1, 7.7, 3.2, 7.9,
+            4.6, 3.4, 5.8, 6.3,
+        ]);
+        triangulator.triangulate();
+        triangulator.insert_marked_segment(
+            Rc::new(Vertex::new(2.0, 5.0)),
+            Rc::new(Vertex::new(8.0, 5.0)),
+            3,
         );
-        assert!(
-            triangulation
-                .triangles
-                .chunks(3)
-                .position(|slice| slice == [1, 3, 2])
-                != None
+        let values: Vec<f64> = (0..triangulator.indexed_vertices.len())
+            .map(|index| index as f64 * 1.5)
+            .collect();
+        triangulator.set_vertex_values(&values);
+        triangulator.set_vertex_marker(Rc::new(Vertex::new(2.3, 2.1)), 7);
+
+        triangulator.relax(5, 0.0);
+
+        /* (2.3, 2.1) moved, keeping its index, marker and value */
+        let triangulation = triangulator.export_preserving_order();
+        assert!(triangulation.coordinates[8] != 2.3 || triangulation.coordinates[9] != 2.1);
+        assert_eq!(triangulation.vertex_markers[4], 7);
+        for (index, value) in values.iter().enumerate() {
+            let vertex = &triangulator.indexed_vertices[index];
+            assert_eq!(triangulator.vertex_values.get(vertex), Some(value));
+        }
+
+        let mut length = 0.0;
+        for ((s1, s2), marker) in triangulator.segments.iter() {
+            assert_eq!(*marker, 3);
+            assert!(
+                triangulator
+                    .adjacency
+                    .contains_key(&(Rc::clone(s1), Rc::clone(s2)))
+                    || triangulator
+                        .adjacency
+                        .contains_key(&(Rc::clone(s2), Rc::clone(s1)))
+            );
+            length += (s2.x - s1.x).abs();
+        }
+        assert!((length - 6.0).abs() < 1e-9);
+    }
+}
+
+#[cfg(test)]
+mod export_tables {
+    use super::*;
+
+    #[test]
+    fn test_neighbors_across_edges() {
+        let vertex_indices = vec![0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0, 0.5, 0.5];
+        let mut triangulator = Triangulator::from_coordinates(vertex_indices);
+        triangulator.triangulate();
+        let triangulation = triangulator.export();
+
+        assert_eq!(triangulation.neighbors.len(), triangulation.triangles.len());
+        let hull_edges = triangulation
+            .neighbors
+            .iter()
+            .filter(|&&neighbor| neighbor == NO_NEIGHBOR)
+            .count();
+        assert_eq!(hull_edges, 4);
+
+        let triangles = &triangulation.triangles;
+        for (index, &neighbor) in triangulation.neighbors.iter().enumerate() {
+            if neighbor == NO_NEIGHBOR {
+                continue;
+            }
+            /* the neighbor holds the same edge in opposite direction */
+            let triangle = index / 3;
+            let v1 = triangles[index];
+            let v2 = triangles[triangle * 3 + (index + 1) % 3];
+            let shares_edge = (0..3).any(|position| {
+                triangles[neighbor * 3 + position] == v2
+                    && triangles[neighbor * 3 + (position + 1) % 3] == v1
+            });
+            assert!(shares_edge);
+        }
+    }
+
+    #[test]
+    fn test_edge_and_vertex_incidence() {
+        let vertex_indices = vec![0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0, 0.5, 0.5];
+        let mut triangulator = Triangulator::from_coordinates(vertex_indices);
+        triangulator.triangulate();
+        let triangulation = triangulator.export();
+
+        /* 4 hull edges and 4 edges to the center */
+        assert_eq!(triangulation.edges.len(), 16);
+        assert_eq!(triangulation.edge_triangles.len(), 16);
+        let interior_edges = triangulation
+            .edge_triangles
+            .chunks(2)
+            .filter(|triangles| triangles[1] != NO_NEIGHBOR)
+            .count();
+        assert_eq!(interior_edges, 4);
+
+        /* the center is the vertex (0.5, 0.5), sorted at index 2 */
+        assert_eq!(triangulation.vertex_triangles.len(), 5);
+        assert_eq!(triangulation.vertex_triangles[2].len(), 4);
+        assert_eq!(triangulation.vertex_triangles[0].len(), 2);
+    }
+}
+
+#[cfg(test)]
+mod export_preserving_order {
+    use super::*;
+
+    #[test]
+    fn test_keeps_input_indices() {
+        let vertex_indices = vec![1.0, 2.0, 2.0, 0.0, 0.0, 0.0, 1.0, 1.0];
+        let mut triangulator = Triangulator::from_coordinates(vertex_indices.clone());
+        triangulator.triangulate();
+        let triangulation = triangulator.export_preserving_order();
+
+        assert_eq!(triangulation.coordinates, vertex_indices);
+        assert_eq!(triangulation.triangles.len(), 9);
+        assert!(triangulation
+            .triangles
+            .chunks(3)
+            .any(|slice| slice == [1, 3, 2]));
+    }
+
+    #[test]
+    fn test_appends_steiner_points() {
+        let vertex_indices = vec![
+            10.0, 0.0, 10.0, 10.0, 0.0, 10.0, 0.0, 0.0, 5.0, 4.9, 5.0, 5.1,
+        ];
+        let mut triangulator = Triangulator::from_coordinates(vertex_indices.clone());
+        triangulator.triangulate();
+        triangulator.insert_segment(
+            Rc::new(Vertex::new(0.0, 5.0)),
+            Rc::new(Vertex::new(10.0, 5.0)),
         );
+        let triangulation = triangulator.export_preserving_order();
+
+        assert_eq!(triangulation.coordinates[..12], vertex_indices[..]);
+        assert_eq!(triangulation.coordinates[12..16], [0.0, 5.0, 10.0, 5.0]);
+        assert!(triangulation.coordinates.len() > 16);
+        for coordinate in triangulation.coordinates[16..].chunks(2) {
+            assert_eq!(coordinate[1], 5.0);
+        }
+    }
+
+    #[test]
+    fn test_keeps_index_of_deleted_vertex() {
+        let vertex_indices = vec![0.0, 0.0, 1.0, 1.0, 2.0, 0.0, 1.0, 2.0];
+        let mut triangulator = Triangulator::from_coordinates(vertex_indices.clone());
+        triangulator.triangulate();
+        triangulator.delete_vertex(Rc::new(Vertex::new(1.0, 1.0)));
+        let triangulation = triangulator.export_preserving_order();
+
+        assert_eq!(triangulation.coordinates, vertex_indices);
+        assert_eq!(triangulation.triangles, vec![0, 2, 3]);
+    }
+
+    #[test]
+    fn test_keeps_index_of_repeated_vertex() {
+        let vertex_indices = vec![0.0, 0.0, 2.0, 0.0, 1.0, 2.0, 2.0, 0.0, 1.0, 0.5];
+        let mut triangulator = Triangulator::from_coordinates(vertex_indices.clone());
+        triangulator.triangulate();
+        let triangulation = triangulator.export_preserving_order();
+
+        assert_eq!(triangulation.coordinates, vertex_indices);
+        assert_eq!(triangulation.triangles.len(), 9);
+        assert!(!triangulation.triangles.contains(&3));
+        assert!(triangulation.vertex_triangles[3].is_empty());
+        assert_eq!(triangulation.vertex_triangles[4].len(), 3);
     }
 }
 
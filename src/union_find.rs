@@ -0,0 +1,39 @@
+/* Disjoint sets over positions 0..size, for grouping triangles into alpha
+shape polygons and vertices into spanning tree components. Roots are found
+by following parents, pointing the starting position straight at its root
+on the way out. */
+
+pub(crate) struct UnionFind {
+    parents: Vec<usize>,
+}
+
+impl UnionFind {
+    pub(crate) fn new(size: usize) -> UnionFind {
+        UnionFind {
+            parents: (0..size).collect(),
+        }
+    }
+
+    pub(crate) fn find(&mut self, position: usize) -> usize {
+        let mut root = position;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+        self.parents[position] = root;
+        root
+    }
+
+    /**
+     * Joins the sets of both positions, the first under the second. Returns the
+     * roots they had, or None if they were already together.
+     */
+    pub(crate) fn union(&mut self, position1: usize, position2: usize) -> Option<(usize, usize)> {
+        let root1 = self.find(position1);
+        let root2 = self.find(position2);
+        if root1 == root2 {
+            return None;
+        }
+        self.parents[root1] = root2;
+        Some((root1, root2))
+    }
+}
@@ -4,10 +4,13 @@ use std::fmt;
 use std::hash::{Hash, Hasher};
 use std::rc::Rc;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vertex {
     pub x: f64,
     pub y: f64,
     pub is_ghost: bool,
+    /* elevation, carried along but ignored for connectivity and equality */
+    pub z: Option<f64>,
 }
 
 impl Hash for Vertex {
@@ -77,6 +80,16 @@ impl Vertex {
             x: x,
             y: y,
             is_ghost: false,
+            z: None,
+        }
+    }
+
+    pub fn new_with_z(x: f64, y: f64, z: f64) -> Self {
+        Self {
+            x,
+            y,
+            is_ghost: false,
+            z: Some(z),
         }
     }
 
@@ -85,6 +98,7 @@ impl Vertex {
             x: 0.0,
             y: 0.0,
             is_ghost: true,
+            z: None,
         }
     }
 
@@ -108,6 +122,31 @@ impl Vertex {
         return vertex_list;
     }
 
+    pub fn from_coordinates_3d(raw_array: Vec<f64>) -> Vec<Rc<Vertex>> {
+        if !raw_array.len().is_multiple_of(3) {
+            panic!("Array must provide vertices by triple of x,y,z coordinates.");
+        }
+
+        raw_array
+            .chunks(3)
+            .map(|chunk| Rc::new(Vertex::new_with_z(chunk[0], chunk[1], chunk[2])))
+            .collect()
+    }
+
+    /**
+     * Mean of the vertices, with an elevation only if all of them have one.
+     */
+    pub fn mean(vertices: &[&Vertex]) -> Vertex {
+        let size = vertices.len() as f64;
+        let z: Option<f64> = vertices.iter().map(|vertex| vertex.z).sum();
+        Vertex {
+            x: vertices.iter().map(|vertex| vertex.x).sum::<f64>() / size,
+            y: vertices.iter().map(|vertex| vertex.y).sum::<f64>() / size,
+            is_ghost: false,
+            z: z.map(|z| z / size),
+        }
+    }
+
     pub fn sort(vertex_list: &mut Vec<Rc<Vertex>>) {
         vertex_list.sort_by(|v1, v2| match v1.x.partial_cmp(&v2.x) {
             Some(Ordering::Equal) => v1.y.partial_cmp(&v2.y).unwrap(),
@@ -166,4 +205,29 @@ mod build_from_coordinates {
         let raw_array = vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 2.0];
         Vertex::from_coordinates(raw_array);
     }
+
+    #[test]
+    fn test_builds_vertices_with_elevation() {
+        let raw_array = vec![0.0, 1.0, 2.0, 4.0, 5.0, 6.0];
+        let vertex_list = Vertex::from_coordinates_3d(raw_array);
+        assert_eq!(vertex_list.len(), 2);
+        assert_eq!(vertex_list[1].z, Some(6.0));
+        assert!(*vertex_list[0] == Vertex::new(0.0, 1.0));
+    }
+}
+
+#[cfg(test)]
+mod mean {
+    use super::*;
+
+    #[test]
+    fn test_mean_keeps_elevation_when_all_have_one() {
+        let v1 = Vertex::new_with_z(0.0, 0.0, 1.0);
+        let v2 = Vertex::new_with_z(2.0, 4.0, 3.0);
+        let mean = Vertex::mean(&[&v1, &v2]);
+        assert_eq!((mean.x, mean.y, mean.z), (1.0, 2.0, Some(2.0)));
+
+        let v3 = Vertex::new(1.0, 1.0);
+        assert_eq!(Vertex::mean(&[&v1, &v3]).z, None);
+    }
 }
@@ -0,0 +1,253 @@
+use crate::triangle::*;
+use crate::vertex::*;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/* Voronoi diagram as the dual of the Delaunay triangulation.
+
+    - Each vertex of the triangulation is the site of a cell.
+    - The circumcenters of the triangles around a site are the
+    vertices of its cell, in counterclockwise order.
+    - Two sites are neighbors if they share a Delaunay edge, which
+    is dual to the Voronoi edge between their cells.
+
+Cells of sites at the convex hull are unbounded. Walking around such
+a site meets two ghost triangles, whose solid edges give the rays
+leaving the first and the last Voronoi vertex towards infinity.
+
+Sites out of the mesh, as deleted vertices, get an empty cell, so the
+cells keep the indices of the sites.  */
+
+pub struct VoronoiCell {
+    pub site: Rc<Vertex>,
+    /* Voronoi vertices as pairs of x,y coordinates, in counterclockwise order */
+    pub vertices: Vec<f64>,
+    /* empty for bounded cells, else the directions of the rays leaving the
+    first and the last vertex, as two pairs of x,y coordinates */
+    pub rays: Vec<f64>,
+    /* sites sharing a Voronoi edge with this cell, in counterclockwise order */
+    pub neighbors: Vec<Rc<Vertex>>,
+}
+
+pub struct Voronoi {
+    pub cells: Vec<VoronoiCell>,
+}
+
+impl VoronoiCell {
+    fn empty(site: &Rc<Vertex>) -> Self {
+        Self {
+            site: Rc::clone(site),
+            vertices: Vec::new(),
+            rays: Vec::new(),
+            neighbors: Vec::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    pub fn is_bounded(&self) -> bool {
+        !self.is_empty() && self.rays.is_empty()
+    }
+
+    /**
+     * Intersects the cell with a simple polygon, given by pairs of x,y coordinates
+     * in counterclockwise order. The polygon is cut by the bisector of the site and
+     * each neighbor, so unbounded cells need no special handling. If the polygon is
+     * not convex and the intersection is disconnected, the parts are joined by
+     * degenerate edges.
+     */
+    pub fn clip(&self, polygon: &[f64]) -> Vec<f64> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+
+        let mut clipped: Vec<f64> = polygon.to_vec();
+        for neighbor in self.neighbors.iter() {
+            clipped = clip_by_bisector(&clipped, &self.site, neighbor);
+            if clipped.is_empty() {
+                break;
+            }
+        }
+        clipped
+    }
+
+    pub fn clip_to_box(&self, x_min: f64, y_min: f64, x_max: f64, y_max: f64) -> Vec<f64> {
+        self.clip(&[x_min, y_min, x_max, y_min, x_max, y_max, x_min, y_max])
+    }
+}
+
+impl Voronoi {
+    /**
+     * Builds a cell for each site by rotating counterclockwise around it: the triangle
+     * following (site, a, b) is the one holding the directed edge (site, b).
+     */
+    pub(crate) fn from_adjacency(
+        sites: &[Rc<Vertex>],
+        adjacency: &HashMap<(Rc<Vertex>, Rc<Vertex>), Rc<Triangle>>,
+    ) -> Self {
+        let mut outgoing_triangles: HashMap<Rc<Vertex>, Rc<Triangle>> = HashMap::new();
+        for ((v1, _), triangle) in adjacency.iter() {
+            if !v1.is_ghost {
+                outgoing_triangles
+                    .entry(Rc::clone(v1))
+                    .or_insert_with(|| Rc::clone(triangle));
+            }
+        }
+
+        let mut cells: Vec<VoronoiCell> = Vec::new();
+        for site in sites.iter() {
+            let cell = outgoing_triangles
+                .get(site)
+                .and_then(|first_triangle| Self::build_cell(site, first_triangle, adjacency));
+            cells.push(cell.unwrap_or_else(|| VoronoiCell::empty(site)));
+        }
+
+        Self { cells }
+    }
+
+    pub fn clip_to_box(&self, x_min: f64, y_min: f64, x_max: f64, y_max: f64) -> Vec<Vec<f64>> {
+        self.cells
+            .iter()
+            .map(|cell| cell.clip_to_box(x_min, y_min, x_max, y_max))
+            .collect()
+    }
+
+    pub fn clip(&self, polygon: &[f64]) -> Vec<Vec<f64>> {
+        self.cells.iter().map(|cell| cell.clip(polygon)).collect()
+    }
+
+    fn build_cell(
+        site: &Rc<Vertex>,
+        first_triangle: &Rc<Triangle>,
+        adjacency: &HashMap<(Rc<Vertex>, Rc<Vertex>), Rc<Triangle>>,
+    ) -> Option<VoronoiCell> {
+        /* triangles around the site, each rotated to (site, a, b) */
+        let mut around: Vec<(Rc<Triangle>, Rc<Vertex>, Rc<Vertex>)> = Vec::new();
+        let mut triangle = Rc::clone(first_triangle);
+        loop {
+            let (a, b) = Self::opposite_edge(&triangle, site);
+            around.push((Rc::clone(&triangle), a, Rc::clone(&b)));
+
+            triangle = match adjacency.get(&(Rc::clone(site), b)) {
+                Some(next) => Rc::clone(next),
+                None => return None,
+            };
+            if triangle == *first_triangle {
+                break;
+            }
+            if around.len() > adjacency.len() {
+                return None;
+            }
+        }
+
+        /* unbounded cells start right after the ghost triangles */
+        let is_bounded = !around.iter().any(|(triangle, _, _)| triangle.is_ghost());
+        if !is_bounded {
+            let size = around.len();
+            let ghost_position = (0..size)
+                .find(|&position| {
+                    around[position].0.is_ghost() && !around[(position + 1) % size].0.is_ghost()
+                })
+                .unwrap();
+            around.rotate_left(ghost_position + 1);
+        }
+
+        let mut vertices: Vec<f64> = Vec::new();
+        let mut neighbors: Vec<Rc<Vertex>> = Vec::new();
+        for (triangle, a, b) in around.iter() {
+            if triangle.is_ghost() {
+                break;
+            }
+            let circumcenter = triangle.circumcenter();
+            vertices.push(circumcenter.x);
+            vertices.push(circumcenter.y);
+            if neighbors.last() != Some(a) {
+                neighbors.push(Rc::clone(a));
+            }
+            neighbors.push(Rc::clone(b));
+        }
+        if neighbors.len() > 1 && neighbors.first() == neighbors.last() {
+            neighbors.pop();
+        }
+        if vertices.is_empty() {
+            return None;
+        }
+
+        /* rays are normal to the hull edges, pointing outwards */
+        let mut rays: Vec<f64> = Vec::new();
+        if !is_bounded {
+            let first_hull_vertex = neighbors.first().unwrap();
+            let last_hull_vertex = neighbors.last().unwrap();
+            rays.push(first_hull_vertex.y - site.y);
+            rays.push(site.x - first_hull_vertex.x);
+            rays.push(site.y - last_hull_vertex.y);
+            rays.push(last_hull_vertex.x - site.x);
+        }
+
+        Some(VoronoiCell {
+            site: Rc::clone(site),
+            vertices,
+            rays,
+            neighbors,
+        })
+    }
+
+    fn opposite_edge(triangle: &Triangle, site: &Rc<Vertex>) -> (Rc<Vertex>, Rc<Vertex>) {
+        if triangle.v1 == *site {
+            (Rc::clone(&triangle.v2), Rc::clone(&triangle.v3))
+        } else if triangle.v2 == *site {
+            (Rc::clone(&triangle.v3), Rc::clone(&triangle.v1))
+        } else {
+            (Rc::clone(&triangle.v1), Rc::clone(&triangle.v2))
+        }
+    }
+}
+
+/**
+ * Sutherland-Hodgman step keeping the part of the polygon closer to the site than
+ * to the neighbor.
+ */
+pub(crate) fn clip_by_bisector(polygon: &[f64], site: &Vertex, neighbor: &Vertex) -> Vec<f64> {
+    /* signed distance, positive on the side of the site */
+    let normal_x = site.x - neighbor.x;
+    let normal_y = site.y - neighbor.y;
+    let middle_x = (site.x + neighbor.x) / 2.0;
+    let middle_y = (site.y + neighbor.y) / 2.0;
+    let side = |x: f64, y: f64| (x - middle_x) * normal_x + (y - middle_y) * normal_y;
+
+    let mut clipped: Vec<f64> = Vec::new();
+    let size = polygon.len() / 2;
+    for index in 0..size {
+        let (x1, y1) = (polygon[index * 2], polygon[index * 2 + 1]);
+        let next = (index + 1) % size;
+        let (x2, y2) = (polygon[next * 2], polygon[next * 2 + 1]);
+        let (side1, side2) = (side(x1, y1), side(x2, y2));
+
+        if side1 >= 0.0 {
+            clipped.push(x1);
+            clipped.push(y1);
+        }
+        if (side1 >= 0.0) != (side2 >= 0.0) {
+            let ratio = side1 / (side1 - side2);
+            clipped.push(x1 + (x2 - x1) * ratio);
+            clipped.push(y1 + (y2 - y1) * ratio);
+        }
+    }
+    clipped
+}
+
+#[cfg(test)]
+mod clip_by_bisector {
+    use super::*;
+
+    #[test]
+    fn test_keeps_site_side() {
+        let square = vec![0.0, 0.0, 2.0, 0.0, 2.0, 2.0, 0.0, 2.0];
+        let site = Vertex::new(0.0, 1.0);
+        let neighbor = Vertex::new(2.0, 1.0);
+        let clipped = clip_by_bisector(&square, &site, &neighbor);
+        assert_eq!(clipped, vec![0.0, 0.0, 1.0, 0.0, 1.0, 2.0, 0.0, 2.0]);
+    }
+}
@@ -0,0 +1,253 @@
+use crate::triangulation::*;
+use std::fmt::Write;
+
+/* VTK unstructured grids, as legacy .vtk and XML .vtu files for ParaView.
+
+    - Points hold x, y and z, z being the elevation or 0 without one.
+    - Cells are written by connectivity, offsets and VTK cell type, so
+    other kinds of cells only need their own type.
+    - Point data holds the boundary markers, and cell data the regional
+    attributes, the minimum angles and the radius-edge ratios, followed
+    by the arrays given, with one value per vertex or per triangle.
+
+Array names should not hold whitespace, which legacy files forbid.  */
+
+pub type DataArray<'a> = (&'a str, &'a [f64]);
+
+const VTK_TRIANGLE: u8 = 5;
+
+pub fn write_vtk(
+    triangulation: &Triangulation,
+    point_data: &[DataArray],
+    cell_data: &[DataArray],
+) -> String {
+    let grid = Grid::new(triangulation);
+    let mut text = String::new();
+    writeln!(text, "# vtk DataFile Version 3.0").unwrap();
+    writeln!(text, "triangulation").unwrap();
+    writeln!(text, "ASCII").unwrap();
+    writeln!(text, "DATASET UNSTRUCTURED_GRID").unwrap();
+
+    writeln!(text, "POINTS {} double", grid.points.len() / 3).unwrap();
+    for point in grid.points.chunks(3) {
+        writeln!(text, "{} {} {}", point[0], point[1], point[2]).unwrap();
+    }
+
+    let cells_size = grid.types.len();
+    writeln!(
+        text,
+        "CELLS {} {}",
+        cells_size,
+        cells_size + grid.connectivity.len()
+    )
+    .unwrap();
+    let mut offset = 0;
+    for end in grid.offsets.iter() {
+        write!(text, "{}", end - offset).unwrap();
+        for vertex in grid.connectivity[offset..*end].iter() {
+            write!(text, " {}", vertex).unwrap();
+        }
+        writeln!(text).unwrap();
+        offset = *end;
+    }
+    writeln!(text, "CELL_TYPES {}", cells_size).unwrap();
+    for cell_type in grid.types.iter() {
+        writeln!(text, "{}", cell_type).unwrap();
+    }
+
+    writeln!(text, "POINT_DATA {}", grid.points.len() / 3).unwrap();
+    for (name, values) in grid.point_arrays(point_data) {
+        write_legacy_scalars(&mut text, name, values);
+    }
+    writeln!(text, "CELL_DATA {}", cells_size).unwrap();
+    for (name, values) in grid.cell_arrays(cell_data) {
+        write_legacy_scalars(&mut text, name, values);
+    }
+    text
+}
+
+pub fn write_vtu(
+    triangulation: &Triangulation,
+    point_data: &[DataArray],
+    cell_data: &[DataArray],
+) -> String {
+    let grid = Grid::new(triangulation);
+    let mut text = String::new();
+    writeln!(text, "<?xml version=\"1.0\"?>").unwrap();
+    writeln!(
+        text,
+        "<VTKFile type=\"UnstructuredGrid\" version=\"0.1\" byte_order=\"LittleEndian\">"
+    )
+    .unwrap();
+    writeln!(text, "  <UnstructuredGrid>").unwrap();
+    writeln!(
+        text,
+        "    <Piece NumberOfPoints=\"{}\" NumberOfCells=\"{}\">",
+        grid.points.len() / 3,
+        grid.types.len()
+    )
+    .unwrap();
+
+    writeln!(text, "      <PointData>").unwrap();
+    for (name, values) in grid.point_arrays(point_data) {
+        write_xml_array(&mut text, "Float64", &format!("Name=\"{}\"", name), values);
+    }
+    writeln!(text, "      </PointData>").unwrap();
+    writeln!(text, "      <CellData>").unwrap();
+    for (name, values) in grid.cell_arrays(cell_data) {
+        write_xml_array(&mut text, "Float64", &format!("Name=\"{}\"", name), values);
+    }
+    writeln!(text, "      </CellData>").unwrap();
+
+    writeln!(text, "      <Points>").unwrap();
+    write_xml_array(
+        &mut text,
+        "Float64",
+        "NumberOfComponents=\"3\"",
+        &grid.points,
+    );
+    writeln!(text, "      </Points>").unwrap();
+
+    writeln!(text, "      <Cells>").unwrap();
+    write_xml_array(
+        &mut text,
+        "Int64",
+        "Name=\"connectivity\"",
+        &grid.connectivity,
+    );
+    write_xml_array(&mut text, "Int64", "Name=\"offsets\"", &grid.offsets);
+    write_xml_array(&mut text, "UInt8", "Name=\"types\"", &grid.types);
+    writeln!(text, "      </Cells>").unwrap();
+
+    writeln!(text, "    </Piece>").unwrap();
+    writeln!(text, "  </UnstructuredGrid>").unwrap();
+    writeln!(text, "</VTKFile>").unwrap();
+    text
+}
+
+/* points, cells and built-in data arrays shared by both formats */
+struct Grid {
+    points: Vec<f64>,
+    connectivity: Vec<usize>,
+    /* end of each cell in the connectivity */
+    offsets: Vec<usize>,
+    types: Vec<u8>,
+    point_data: Vec<(&'static str, Vec<f64>)>,
+    cell_data: Vec<(&'static str, Vec<f64>)>,
+}
+
+impl Grid {
+    fn new(triangulation: &Triangulation) -> Self {
+        let vertices_size = triangulation.coordinates.len() / 2;
+        let mut points: Vec<f64> = Vec::new();
+        for index in 0..vertices_size {
+            points.push(triangulation.coordinates[index * 2]);
+            points.push(triangulation.coordinates[index * 2 + 1]);
+            points.push(*triangulation.z.get(index).unwrap_or(&0.0));
+        }
+
+        let triangles_size = triangulation.triangles.len() / 3;
+        let markers: Vec<f64> = (0..vertices_size)
+            .map(|index| *triangulation.vertex_markers.get(index).unwrap_or(&0) as f64)
+            .collect();
+        let attributes: Vec<f64> = (0..triangles_size)
+            .map(|index| *triangulation.attributes.get(index).unwrap_or(&0.0))
+            .collect();
+
+        Self {
+            points,
+            connectivity: triangulation.triangles.clone(),
+            offsets: (1..=triangles_size).map(|index| index * 3).collect(),
+            types: vec![VTK_TRIANGLE; triangles_size],
+            point_data: vec![("marker", markers)],
+            cell_data: vec![
+                ("attribute", attributes),
+                ("min_angle", triangulation.min_angles()),
+                ("radius_edge_ratio", triangulation.radius_edge_ratios()),
+            ],
+        }
+    }
+
+    /* built-in arrays followed by the given ones */
+    fn point_arrays<'a>(&'a self, given: &[DataArray<'a>]) -> Vec<DataArray<'a>> {
+        Self::arrays(&self.point_data, given)
+    }
+
+    fn cell_arrays<'a>(&'a self, given: &[DataArray<'a>]) -> Vec<DataArray<'a>> {
+        Self::arrays(&self.cell_data, given)
+    }
+
+    fn arrays<'a>(
+        built_in: &'a [(&'static str, Vec<f64>)],
+        given: &[DataArray<'a>],
+    ) -> Vec<DataArray<'a>> {
+        built_in
+            .iter()
+            .map(|(name, values)| (*name, values.as_slice()))
+            .chain(given.iter().copied())
+            .collect()
+    }
+}
+
+fn write_legacy_scalars(text: &mut String, name: &str, values: &[f64]) {
+    writeln!(text, "SCALARS {} double 1", name).unwrap();
+    writeln!(text, "LOOKUP_TABLE default").unwrap();
+    for value in values.iter() {
+        writeln!(text, "{}", value).unwrap();
+    }
+}
+
+fn write_xml_array<T: ToString>(
+    text: &mut String,
+    data_type: &str,
+    attributes: &str,
+    values: &[T],
+) {
+    writeln!(
+        text,
+        "        <DataArray type=\"{}\" {} format=\"ascii\">",
+        data_type, attributes
+    )
+    .unwrap();
+    let line: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+    writeln!(text, "          {}", line.join(" ")).unwrap();
+    writeln!(text, "        </DataArray>").unwrap();
+}
+
+#[cfg(test)]
+mod write_vtk {
+    use super::*;
+
+    fn square() -> Triangulation {
+        let mut triangulation = Triangulation::from(
+            vec![0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0],
+            vec![0, 1, 2, 0, 2, 3],
+        );
+        triangulation.attributes = vec![1.0, 2.0];
+        triangulation
+    }
+
+    #[test]
+    fn test_legacy() {
+        let text = write_vtk(&square(), &[("pressure", &[0.5, 1.5, 2.5, 3.5])], &[]);
+        assert!(text.starts_with("# vtk DataFile Version 3.0\ntriangulation\nASCII\n"));
+        assert!(text.contains("POINTS 4 double\n0 0 0\n1 0 0\n1 1 0\n0 1 0\n"));
+        assert!(text.contains("CELLS 2 8\n3 0 1 2\n3 0 2 3\nCELL_TYPES 2\n5\n5\n"));
+        assert!(text.contains("SCALARS pressure double 1\nLOOKUP_TABLE default\n0.5\n1.5\n"));
+        assert!(
+            text.contains("CELL_DATA 2\nSCALARS attribute double 1\nLOOKUP_TABLE default\n1\n2\n")
+        );
+        assert!(text.contains("SCALARS min_angle double 1"));
+    }
+
+    #[test]
+    fn test_xml() {
+        let text = write_vtu(&square(), &[], &[("error", &[0.1, 0.2])]);
+        assert!(text.contains("<Piece NumberOfPoints=\"4\" NumberOfCells=\"2\">"));
+        assert!(text.contains("Name=\"connectivity\" format=\"ascii\">\n          0 1 2 0 2 3\n"));
+        assert!(text.contains("Name=\"offsets\" format=\"ascii\">\n          3 6\n"));
+        assert!(text.contains("Name=\"types\" format=\"ascii\">\n          5 5\n"));
+        assert!(text.contains("Name=\"error\" format=\"ascii\">\n          0.1 0.2\n"));
+        assert!(text.trim_end().ends_with("</VTKFile>"));
+    }
+}
@@ -0,0 +1,262 @@
+use crate::location::*;
+use crate::triangulator::*;
+use crate::vertex::*;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+
+/* JavaScript bindings, built with the wasm feature through wasm-pack.
+
+    const triangulator = new Triangulator(new Float64Array([0, 0, 1, 0, 0, 1]));
+    triangulator.triangulate();
+    triangulator.insertVertex(0.2, 0.2);
+    const mesh = triangulator.export();
+    mesh.coordinates; // Float64Array of x,y pairs
+    mesh.triangles;   // Uint32Array of vertex index triples
+
+Vertices keep the index they arrived with, Steiner points following.
+Invalid input throws an Error with a message instead of aborting the
+module: coordinates not in pairs, vertices spanning no triangle, edits
+before `triangulate`, and vertices inserted in a hole.  */
+
+#[wasm_bindgen(js_name = Triangulator)]
+pub struct WasmTriangulator {
+    triangulator: Triangulator,
+}
+
+#[wasm_bindgen(js_name = Triangulation)]
+pub struct WasmTriangulation {
+    coordinates: Vec<f64>,
+    triangles: Vec<u32>,
+}
+
+#[wasm_bindgen(js_class = Triangulator)]
+impl WasmTriangulator {
+    /**
+     * Takes pairs of x,y coordinates, triangulated on `triangulate`.
+     */
+    #[wasm_bindgen(constructor)]
+    pub fn new(coordinates: &[f64]) -> Result<WasmTriangulator, JsValue> {
+        check_pairs(coordinates)?;
+        Ok(Self {
+            triangulator: Triangulator::from_coordinates(coordinates.to_vec()),
+        })
+    }
+
+    /**
+     * Fails unless the vertices span a triangle, at least three distinct ones
+     * not all on a line.
+     */
+    pub fn triangulate(&mut self) -> Result<(), JsValue> {
+        if !self.triangulator.spans_triangle() {
+            return Err(JsValue::from_str(
+                "expected 3 vertices or more, not all on a line",
+            ));
+        }
+        self.triangulator.triangulate();
+        Ok(())
+    }
+
+    /**
+     * Inserts a vertex within the mesh or beyond its hull, not in a hole.
+     */
+    #[wasm_bindgen(js_name = insertVertex)]
+    pub fn insert_vertex(&mut self, x: f64, y: f64) -> Result<(), JsValue> {
+        self.check_triangulated()?;
+        if let Some(Location::OnVertex(_)) = self.triangulator.locate(x, y) {
+            return Err(JsValue::from_str("vertex already inserted"));
+        }
+        let vertex = Rc::new(Vertex::new(x, y));
+        if !self.triangulator.can_insert_vertex(&vertex) {
+            return Err(JsValue::from_str("cannot insert a vertex in a hole"));
+        }
+        self.triangulator.insert_vertex(vertex);
+        Ok(())
+    }
+
+    /**
+     * Deletes an inner vertex. Vertices on the hull or on a hole boundary
+     * cannot be deleted.
+     */
+    #[wasm_bindgen(js_name = deleteVertex)]
+    pub fn delete_vertex(&mut self, x: f64, y: f64) -> Result<(), JsValue> {
+        match self.triangulator.locate(x, y) {
+            Some(Location::OnVertex(vertex)) => {
+                if self.triangulator.is_boundary_vertex(&vertex) {
+                    return Err(JsValue::from_str("cannot delete a boundary vertex"));
+                }
+                self.triangulator.delete_vertex(vertex);
+                Ok(())
+            }
+            _ => Err(JsValue::from_str("no vertex at the given position")),
+        }
+    }
+
+    /**
+     * Takes the closed boundary of the hole as pairs of x,y coordinates.
+     */
+    #[wasm_bindgen(js_name = insertHole)]
+    pub fn insert_hole(&mut self, coordinates: &[f64]) -> Result<(), JsValue> {
+        self.check_triangulated()?;
+        check_pairs(coordinates)?;
+        if coordinates.len() < 6 {
+            return Err(JsValue::from_str("a hole needs 3 vertices or more"));
+        }
+        let vertices = Vertex::from_coordinates(coordinates.to_vec());
+        for vertex in vertices.iter() {
+            let is_known = matches!(
+                self.triangulator.locate(vertex.x, vertex.y),
+                Some(Location::OnVertex(_))
+            );
+            if !is_known && !self.triangulator.can_insert_vertex(vertex) {
+                return Err(JsValue::from_str("cannot insert a vertex in a hole"));
+            }
+        }
+        self.triangulator.insert_hole(vertices);
+        Ok(())
+    }
+
+    pub fn export(&self) -> WasmTriangulation {
+        let triangulation = self.triangulator.export_preserving_order();
+        WasmTriangulation {
+            coordinates: triangulation.coordinates,
+            triangles: triangulation
+                .triangles
+                .iter()
+                .map(|vertex| *vertex as u32)
+                .collect(),
+        }
+    }
+}
+
+impl WasmTriangulator {
+    fn check_triangulated(&self) -> Result<(), JsValue> {
+        if !self.triangulator.is_triangulated() {
+            return Err(JsValue::from_str("expected a call to triangulate first"));
+        }
+        Ok(())
+    }
+}
+
+#[wasm_bindgen(js_class = Triangulation)]
+impl WasmTriangulation {
+    /**
+     * Pairs of x,y coordinates, as a Float64Array.
+     */
+    #[wasm_bindgen(getter)]
+    pub fn coordinates(&self) -> Vec<f64> {
+        self.coordinates.clone()
+    }
+
+    /**
+     * Triples of vertex indices, counterclockwise, as a Uint32Array.
+     */
+    #[wasm_bindgen(getter)]
+    pub fn triangles(&self) -> Vec<u32> {
+        self.triangles.clone()
+    }
+}
+
+fn check_pairs(coordinates: &[f64]) -> Result<(), JsValue> {
+    if !coordinates.len().is_multiple_of(2) {
+        return Err(JsValue::from_str("expected pairs of x,y coordinates"));
+    }
+    Ok(())
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod wasm_triangulator {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    fn square() -> WasmTriangulator {
+        let mut triangulator =
+            WasmTriangulator::new(&[0.0, 0.0, 4.0, 0.0, 4.0, 4.0, 0.0, 4.0, 1.3, 1.1]).unwrap();
+        triangulator.triangulate().unwrap();
+        triangulator
+    }
+
+    #[wasm_bindgen_test]
+    fn test_triangulate_and_export() {
+        let mesh = square().export();
+        assert_eq!(mesh.coordinates()[8..], [1.3, 1.1]);
+        assert_eq!(mesh.triangles().len(), 4 * 3);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_insert_and_delete_vertex() {
+        let mut triangulator = square();
+        triangulator.insert_vertex(2.9, 2.7).unwrap();
+        assert_eq!(triangulator.export().triangles().len(), 6 * 3);
+        assert!(triangulator.insert_vertex(2.9, 2.7).is_err());
+
+        triangulator.delete_vertex(1.3, 1.1).unwrap();
+        assert_eq!(triangulator.export().triangles().len(), 4 * 3);
+        assert!(triangulator.delete_vertex(0.0, 0.0).is_err());
+        assert!(triangulator.delete_vertex(3.0, 0.5).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_insert_hole() {
+        let mut triangulator = square();
+        triangulator
+            .insert_hole(&[2.0, 2.0, 3.0, 2.0, 3.0, 3.0, 2.0, 3.0])
+            .unwrap();
+        let mesh = triangulator.export();
+        let coordinates = mesh.coordinates();
+        let area: f64 = mesh
+            .triangles()
+            .chunks(3)
+            .map(|triangle| {
+                let (a, b, c) = (
+                    triangle[0] as usize * 2,
+                    triangle[1] as usize * 2,
+                    triangle[2] as usize * 2,
+                );
+                ((coordinates[b] - coordinates[a]) * (coordinates[c + 1] - coordinates[a + 1])
+                    - (coordinates[b + 1] - coordinates[a + 1]) * (coordinates[c] - coordinates[a]))
+                    / 2.0
+            })
+            .sum();
+        assert!((area - 15.0).abs() < 1e-9);
+        assert!(triangulator.insert_hole(&[0.0, 0.0, 1.0]).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_rejects_degenerate_vertices() {
+        let mut triangulator = WasmTriangulator::new(&[0.0, 0.0, 1.0, 1.0]).unwrap();
+        assert!(triangulator.triangulate().is_err());
+        let mut triangulator =
+            WasmTriangulator::new(&[0.0, 0.0, 1.0, 1.0, 2.0, 2.0, 3.0, 3.0]).unwrap();
+        assert!(triangulator.triangulate().is_err());
+        assert!(WasmTriangulator::new(&[0.0, 0.0, 1.0]).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_rejects_edits_before_triangulating() {
+        let mut triangulator = WasmTriangulator::new(&[0.0, 0.0, 4.0, 0.0, 0.0, 4.0]).unwrap();
+        assert!(triangulator.insert_vertex(1.0, 1.0).is_err());
+        assert!(triangulator
+            .insert_hole(&[1.0, 1.0, 2.0, 1.0, 1.0, 2.0])
+            .is_err());
+        assert!(triangulator.delete_vertex(0.0, 0.0).is_err());
+        triangulator.triangulate().unwrap();
+        triangulator.insert_vertex(1.0, 1.0).unwrap();
+    }
+
+    #[wasm_bindgen_test]
+    fn test_rejects_vertices_in_hole() {
+        let mut triangulator = square();
+        triangulator
+            .insert_hole(&[2.0, 2.0, 3.0, 2.0, 3.0, 3.0, 2.0, 3.0])
+            .unwrap();
+        assert!(triangulator.insert_vertex(2.5, 2.5).is_err());
+        assert!(triangulator
+            .insert_hole(&[2.2, 2.2, 2.8, 2.2, 2.8, 2.8])
+            .is_err());
+
+        /* around the hole and beyond the hull vertices still go in */
+        triangulator.insert_vertex(1.0, 3.0).unwrap();
+        triangulator.insert_vertex(5.0, 2.0).unwrap();
+        assert_eq!(triangulator.export().coordinates().len(), 11 * 2);
+    }
+}